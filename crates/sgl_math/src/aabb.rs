@@ -1,5 +1,6 @@
 use crate::Vec2;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Aabb {
     min: Vec2,
     max: Vec2,
@@ -10,6 +11,14 @@ impl Aabb {
         Self { min, max }
     }
 
+    pub fn min(&self) -> Vec2 {
+        self.min
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.max
+    }
+
     pub fn width(&self) -> f32 {
         self.max.x - self.min.x
     }
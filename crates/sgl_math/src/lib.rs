@@ -1,5 +1,7 @@
 pub use aabb::Aabb;
+pub use transform::Transform;
 pub use vec2::{v2, Vec2};
 
 mod aabb;
+mod transform;
 mod vec2;
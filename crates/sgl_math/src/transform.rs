@@ -0,0 +1,192 @@
+use std::ops::{Mul, MulAssign};
+
+use crate::{v2, Vec2};
+
+/// A 2D affine transform. Points are mapped as `x_axis * x + y_axis * y + translation`.
+///
+/// Multiplying `a * b` produces a transform that applies `b` first, then `a`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub translation: Vec2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        x_axis: Vec2 { x: 1.0, y: 0.0 },
+        y_axis: Vec2 { x: 0.0, y: 1.0 },
+        translation: Vec2 { x: 0.0, y: 0.0 },
+    };
+
+    pub fn translate(translation: Vec2) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotate by `angle` radians. With y pointing down this turns clockwise on screen.
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Self {
+            x_axis: v2(cos, sin),
+            y_axis: v2(-sin, cos),
+            translation: v2(0.0, 0.0),
+        }
+    }
+
+    pub fn scale(scale: Vec2) -> Self {
+        Self {
+            x_axis: v2(scale.x, 0.0),
+            y_axis: v2(0.0, scale.y),
+            translation: v2(0.0, 0.0),
+        }
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.x_axis * point.x + self.y_axis * point.y + self.translation
+    }
+
+    /// Transform a direction, ignoring translation.
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        self.x_axis * vector.x + self.y_axis * vector.y
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.x_axis.x * self.y_axis.y - self.y_axis.x * self.x_axis.y
+    }
+
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.determinant();
+
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let x_axis = v2(self.y_axis.y, -self.x_axis.y) / det;
+        let y_axis = v2(-self.y_axis.x, self.x_axis.x) / det;
+        let translation = -(x_axis * self.translation.x + y_axis * self.translation.y);
+
+        Some(Self {
+            x_axis,
+            y_axis,
+            translation,
+        })
+    }
+
+    /// The largest factor a unit length can be stretched by.
+    pub fn max_scale(&self) -> f32 {
+        f32::max(self.x_axis.len(), self.y_axis.len())
+    }
+}
+
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self {
+            x_axis: self.transform_vector(rhs.x_axis),
+            y_axis: self.transform_vector(rhs.y_axis),
+            translation: self.transform_point(rhs.translation),
+        }
+    }
+}
+
+impl MulAssign for Transform {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn assert_near(expected: Vec2, actual: Vec2) {
+        assert!(
+            (expected - actual).len() < 1e-5,
+            "expected {expected:?} but got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn identity_leaves_points_unchanged() {
+        assert_eq!(
+            v2(3.0, 4.0),
+            Transform::IDENTITY.transform_point(v2(3.0, 4.0))
+        );
+    }
+
+    #[test]
+    fn translate_point() {
+        let t = Transform::translate(v2(10.0, 20.0));
+
+        assert_eq!(v2(13.0, 24.0), t.transform_point(v2(3.0, 4.0)));
+        assert_eq!(v2(3.0, 4.0), t.transform_vector(v2(3.0, 4.0)));
+    }
+
+    #[test]
+    fn rotate_point() {
+        let t = Transform::rotate(FRAC_PI_2);
+
+        assert_near(v2(0.0, 1.0), t.transform_point(v2(1.0, 0.0)));
+        assert_near(v2(-1.0, 0.0), t.transform_point(v2(0.0, 1.0)));
+    }
+
+    #[test]
+    fn scale_point() {
+        let t = Transform::scale(v2(2.0, 3.0));
+
+        assert_eq!(v2(6.0, 12.0), t.transform_point(v2(3.0, 4.0)));
+    }
+
+    #[test]
+    fn multiplication_applies_right_hand_side_first() {
+        let t = Transform::translate(v2(10.0, 0.0)) * Transform::scale(v2(2.0, 2.0));
+
+        assert_eq!(v2(12.0, 2.0), t.transform_point(v2(1.0, 1.0)));
+    }
+
+    #[test]
+    fn mul_assign_transform() {
+        let mut t = Transform::translate(v2(10.0, 0.0));
+        t *= Transform::rotate(FRAC_PI_2);
+
+        assert_near(v2(10.0, 1.0), t.transform_point(v2(1.0, 0.0)));
+    }
+
+    #[test]
+    fn inverse_undoes_transform() {
+        let t = Transform::translate(v2(10.0, -5.0))
+            * Transform::rotate(0.3)
+            * Transform::scale(v2(2.0, 0.5));
+        let inverse = t.inverse().expect("invertible");
+
+        assert_near(
+            v2(3.0, 4.0),
+            inverse.transform_point(t.transform_point(v2(3.0, 4.0))),
+        );
+    }
+
+    #[test]
+    fn degenerate_transform_has_no_inverse() {
+        assert_eq!(None, Transform::scale(v2(0.0, 1.0)).inverse());
+    }
+
+    #[test]
+    fn max_scale_of_transform() {
+        let t = Transform::rotate(0.7) * Transform::scale(v2(2.0, 3.0));
+
+        assert!((3.0 - t.max_scale()).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,56 @@
+use sgl::{Canvas, GraphicsDevice, Key, Pixel, Renderer, SglError, Upscale, Window};
+
+const SAND: Pixel = Pixel::rgb(0xe0, 0xc0, 0x60);
+
+fn main() -> Result<(), SglError> {
+    let mut window = Window::new(160, 120, "Canvas example", 4, 4)?.with_low_res(Upscale::Integer);
+    let mut gpu = GraphicsDevice::new(&window)?;
+    let renderer = Renderer::new(&gpu, &window)?;
+    let mut canvas = Canvas::new(&gpu, &window);
+
+    let mut frame: u32 = 0;
+
+    while !window.closed() && !window.key_down(Key::Escape) {
+        window.update();
+
+        let mouse = window.mouse_position();
+        if mouse.x >= 0.0 && mouse.y >= 0.0 {
+            canvas.set_pixel(mouse.x as u32, mouse.y as u32, SAND);
+        }
+        canvas.set_pixel(80 + frame % 7, 0, SAND);
+
+        // Grains fall straight down, or slide off to either side when blocked.
+        for y in (0..canvas.height() - 1).rev() {
+            for x in 0..canvas.width() {
+                if canvas.pixel(x, y) != Some(SAND) {
+                    continue;
+                }
+
+                let side = if (x + y + frame).is_multiple_of(2) {
+                    1
+                } else {
+                    -1
+                };
+                let below = [0, side, -side]
+                    .map(|dx| (x as i32 + dx) as u32)
+                    .into_iter()
+                    .find(|&to| canvas.pixel(to, y + 1) == Some(Pixel::BLACK));
+
+                if let Some(to) = below {
+                    canvas.set_pixel(x, y, Pixel::BLACK);
+                    canvas.set_pixel(to, y + 1, SAND);
+                }
+            }
+        }
+
+        let mut scene = renderer.begin_scene(&window);
+        scene.clear(Pixel::BLACK);
+        scene.draw_canvas(&canvas);
+
+        renderer.end_scene(scene, &mut gpu);
+
+        frame += 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use sgl::{GraphicsDevice, Key, Pixel, Renderer, SglError, Upscale, Window};
+use sgl_math::v2;
+
+fn main() -> Result<(), SglError> {
+    let mut window =
+        Window::new(160, 120, "Low resolution example", 4, 4)?.with_low_res(Upscale::Integer);
+    let mut gpu = GraphicsDevice::new(&window)?;
+    let renderer = Renderer::new(&gpu, &window)?;
+
+    let mut time: f32 = 0.0;
+
+    while !window.closed() && !window.key_down(Key::Escape) {
+        window.update();
+
+        // Rotated edges stay on the 160x120 grid instead of being drawn at window resolution.
+        let center = v2(80.0, 60.0);
+        let corners: Vec<_> = (0..4)
+            .map(|i| {
+                let angle = time + i as f32 * std::f32::consts::FRAC_PI_2;
+                center + v2(angle.cos(), angle.sin()) * 40.0
+            })
+            .collect();
+
+        let mut scene = renderer.begin_scene(&window);
+        scene.clear(Pixel::rgb(0x1f, 0x1f, 0x3f));
+        scene.draw_filled_polygon(&corners, Pixel::YELLOW);
+        scene.draw_circle(center, 50.0, Pixel::WHITE, 1.0);
+        scene.draw_filled_circle(window.mouse_position(), 3.0, Pixel::RED);
+
+        renderer.end_scene(scene, &mut gpu);
+
+        time += 1.0 / 60.0;
+    }
+
+    Ok(())
+}
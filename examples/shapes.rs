@@ -0,0 +1,133 @@
+use std::{
+    f32::consts::{FRAC_PI_2, TAU},
+    time::Instant,
+};
+
+use sgl::{
+    FillRule, GraphicsDevice, Key, LineCap, LineJoin, Paint, Path, Pixel, PointStyle, RenderStats,
+    Renderer, SglError, Stroke, Window,
+};
+
+fn main() -> Result<(), SglError> {
+    let mut window = Window::new(640, 480, "Shapes example", 1, 1)?;
+    let mut gpu = GraphicsDevice::new(&window)?;
+    let renderer = Renderer::new(&gpu, &window)?;
+
+    let heart = Path::new()
+        .move_to([540.0, 230.0])
+        .cubic_to([540.0, 200.0], [490.0, 190.0], [490.0, 230.0])
+        .cubic_to([490.0, 260.0], [530.0, 280.0], [540.0, 300.0])
+        .cubic_to([550.0, 280.0], [590.0, 260.0], [590.0, 230.0])
+        .cubic_to([590.0, 190.0], [540.0, 200.0], [540.0, 230.0])
+        .close();
+
+    let stars: Vec<([f32; 2], Pixel)> = (0..200)
+        .map(|i| {
+            let x = (i * 97 % 640) as f32;
+            let y = 320.0 + (i * 61 % 160) as f32;
+            let brightness = (64 + i * 37 % 192) as u8;
+            ([x, y], Pixel::rgb(brightness, brightness, brightness))
+        })
+        .collect();
+
+    let mut health: f32 = 0.0;
+    let mut stats = RenderStats::default();
+    let mut frame_start = Instant::now();
+
+    while !window.closed() && !window.key_down(Key::Escape) {
+        window.update();
+
+        let mut scene = renderer.begin_scene(&window);
+        scene.clear(Pixel::rgb(0x1f, 0x1f, 0xdf));
+        scene.draw_filled_rect(
+            [0.0, 0.0],
+            [640.0, 480.0],
+            Paint::linear_gradient(
+                [0.0, 0.0],
+                [0.0, 480.0],
+                Pixel::rgb(0x1f, 0x1f, 0xdf),
+                Pixel::rgb(0xff, 0x9f, 0x5f),
+            ),
+        );
+        scene.draw_filled_rect_corners(
+            [40.0, 400.0],
+            [240.0, 440.0],
+            [Pixel::RED, Pixel::YELLOW, Pixel::GREEN, Pixel::WHITE],
+        );
+        scene.draw_filled_circle(
+            [80.0, 80.0],
+            50.0,
+            Paint::radial_gradient([70.0, 70.0], 60.0, Pixel::WHITE, Pixel::GREEN),
+        );
+        scene.set_anti_aliased(true);
+        scene.draw_circle([80.0, 80.0], 50.0, Pixel::WHITE, 3.0);
+        scene.draw_filled_rounded_rect([680.0, 40.0], [840.0, 100.0], [12.0; 4], Pixel::BLACK);
+        scene.draw_rounded_rect([680.0, 40.0], [840.0, 100.0], [12.0; 4], Pixel::WHITE, 2.0);
+        scene.set_anti_aliased(false);
+        scene.draw_filled_ellipse([240.0, 80.0], [80.0, 40.0], Pixel::YELLOW);
+        scene.draw_ellipse([240.0, 80.0], [80.0, 40.0], Pixel::BLACK, 2.0);
+        scene.draw_arc(
+            [420.0, 80.0],
+            50.0,
+            -FRAC_PI_2,
+            health * TAU,
+            Pixel::RED,
+            8.0,
+        );
+        scene.draw_filled_sector([560.0, 80.0], 50.0, 0.0, 1.5 * FRAC_PI_2, Pixel::RED);
+        scene.draw_sector([560.0, 80.0], 50.0, 0.0, 1.5 * FRAC_PI_2, Pixel::WHITE, 2.0);
+
+        scene.draw_points_with_style(&stars, 2.0, PointStyle::Round);
+
+        let outline = [
+            [40.0, 180.0],
+            [200.0, 180.0],
+            [200.0, 300.0],
+            [120.0, 240.0],
+            [40.0, 300.0],
+        ];
+        let hole = [[70.0, 200.0], [170.0, 200.0], [170.0, 220.0], [70.0, 220.0]];
+        scene.draw_filled_polygon_with_holes(&outline, &[hole], Pixel::GREEN);
+        scene.draw_polygon_with_holes(&outline, &[hole], Pixel::WHITE, 2.0);
+
+        let zigzag = [
+            [260.0, 300.0],
+            [320.0, 180.0],
+            [380.0, 300.0],
+            [440.0, 180.0],
+        ];
+        let joins = [LineJoin::Miter, LineJoin::Bevel, LineJoin::Round];
+        let caps = [LineCap::Butt, LineCap::Square, LineCap::Round];
+        for (i, (join, cap)) in joins.into_iter().zip(caps).enumerate() {
+            let offset = i as f32 * 50.0;
+            let points = zigzag.map(|[x, y]| [x, y + offset]);
+            let stroke = Stroke::new(12.0).with_join(join).with_cap(cap);
+            scene.draw_polyline(&points, Pixel::YELLOW, stroke);
+        }
+
+        scene.fill_path(&heart, Pixel::RED, FillRule::NonZero);
+        scene.stroke_path(&heart, Pixel::WHITE, 3.0);
+        scene.draw_stroked_rect(
+            [480.0, 180.0],
+            [600.0, 310.0],
+            Pixel::WHITE,
+            Stroke::new(2.0)
+                .with_dashes(&[6.0, 4.0])
+                .with_dash_offset(health * 100.0),
+        );
+
+        let frame_time = frame_start.elapsed();
+        frame_start = Instant::now();
+        scene.debug_text(&format!("fps: {:.0}", 1.0 / frame_time.as_secs_f32()));
+        scene.debug_text(&format!(
+            "draw calls: {}\nvertices: {}",
+            stats.draw_calls, stats.vertices
+        ));
+
+        stats = renderer.end_scene(scene, &mut gpu);
+
+        health = (health + 0.002) % 1.0;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use sgl::{Camera, Follow, GraphicsDevice, Key, Pixel, Renderer, SglError, View, Window};
+use sgl_math::{v2, Aabb, Vec2};
+
+const WORLD_SIZE: f32 = 1000.0;
+
+fn draw_world(scene: &mut sgl::Scene, players: &[Vec2; 2]) {
+    scene.draw_filled_rect(
+        [0.0, 0.0],
+        [WORLD_SIZE, WORLD_SIZE],
+        Pixel::rgb(0x20, 0x60, 0x20),
+    );
+
+    for i in 0..=10 {
+        let offset = i as f32 * WORLD_SIZE / 10.0;
+        scene.draw_line(
+            [offset, 0.0],
+            [offset, WORLD_SIZE],
+            Pixel::rgb(0x30, 0x80, 0x30),
+            2.0,
+        );
+        scene.draw_line(
+            [0.0, offset],
+            [WORLD_SIZE, offset],
+            Pixel::rgb(0x30, 0x80, 0x30),
+            2.0,
+        );
+    }
+
+    scene.draw_filled_circle(players[0], 16.0, Pixel::RED);
+    scene.draw_filled_circle(players[1], 16.0, Pixel::YELLOW);
+}
+
+fn main() -> Result<(), SglError> {
+    let mut window = Window::new(640, 480, "Split screen example", 1, 1)?;
+    let mut gpu = GraphicsDevice::new(&window)?;
+    let renderer = Renderer::new(&gpu, &window)?;
+
+    let left = Aabb::new(v2(0.0, 0.0), v2(0.5, 1.0));
+    let right = Aabb::new(v2(0.5, 0.0), v2(1.0, 1.0));
+    let minimap = Aabb::new(v2(0.4, 0.02), v2(0.6, 0.02 + 0.2 * 640.0 / 480.0));
+
+    let world = Aabb::new(v2(0.0, 0.0), v2(WORLD_SIZE, WORLD_SIZE));
+    let mut cameras = [left, right].map(|viewport| {
+        Camera::new(View::new([0.0, 0.0], 320.0, 480.0).with_viewport(viewport))
+            .with_follow(Follow::Spring(6.0))
+            .with_deadzone(60.0, 60.0)
+            .with_bounds(world)
+            .with_pixel_snap(window.pixel_size())
+    });
+
+    let mut time: f32 = 0.0;
+
+    while !window.closed() && !window.key_down(Key::Escape) {
+        window.update();
+
+        let center = v2(WORLD_SIZE / 2.0, WORLD_SIZE / 2.0);
+        let players = [
+            center + v2(time.cos() * 300.0, time.sin() * 200.0),
+            center + v2((time * 0.7).sin() * 250.0, (time * 1.3).cos() * 350.0),
+        ];
+
+        let mut scene = renderer.begin_scene(&window);
+        scene.clear(Pixel::BLACK);
+
+        for (camera, player) in cameras.iter_mut().zip(players) {
+            if (players[0] - players[1]).len() < 40.0 {
+                camera.add_trauma(0.1);
+            }
+
+            camera.follow(player);
+            camera.update(1.0 / 60.0);
+
+            scene.set_view(camera.view());
+            draw_world(&mut scene, &players);
+        }
+
+        scene.set_view(View::new(center, WORLD_SIZE, WORLD_SIZE).with_viewport(minimap));
+        draw_world(&mut scene, &players);
+
+        scene.set_view(window.view());
+        scene.draw_line([320.0, 0.0], [320.0, 480.0], Pixel::WHITE, 2.0);
+
+        renderer.end_scene(scene, &mut gpu);
+
+        time += 0.01;
+    }
+
+    Ok(())
+}
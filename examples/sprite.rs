@@ -1,4 +1,4 @@
-use sgl::{load_file, Bitmap, GraphicsDevice, Key, Pixel, Renderer, SglError, Window};
+use sgl::{load_file, Bitmap, GraphicsDevice, Key, Pixel, Renderer, SglError, Sprite, Window};
 
 fn main() -> Result<(), SglError> {
     let mut window = Window::new(256, 192, "Sprite example", 1, 1)?;
@@ -16,6 +16,8 @@ fn main() -> Result<(), SglError> {
     let h = ufo_bitmap.height() as f32;
     let center = [w / 2.0, h / 2.0];
 
+    let mut angle: f32 = 0.0;
+
     while !window.closed() && !window.key_down(Key::Escape) {
         window.update();
 
@@ -29,6 +31,16 @@ fn main() -> Result<(), SglError> {
             center,
             [w, h],
         );
+        scene.draw_sprite(
+            &wizard,
+            Sprite::new([160.0, 120.0])
+                .with_origin([8.0, 8.0])
+                .with_rotation(angle)
+                .with_scale([3.0, 3.0])
+                .with_flip(angle.sin() < 0.0, false)
+                .with_color(Pixel::rgb(0xff, 0xc0, 0xc0)),
+        );
+        angle += 0.01;
 
         renderer.end_scene(scene, &mut gpu);
     }
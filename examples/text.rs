@@ -0,0 +1,67 @@
+use sgl::{
+    Font, GraphicsDevice, Key, Pixel, Renderer, SglError, TextAlign, TextLayout, TextStyle,
+    VerticalAlign, Window,
+};
+
+fn main() -> Result<(), SglError> {
+    let mut window = Window::new(640, 480, "Text example", 1, 1)?;
+    let mut gpu = GraphicsDevice::new(&window)?;
+    let renderer = Renderer::new(&gpu, &window)?;
+
+    let font = Font::load_file(&gpu, "examples/assets/FiraSans-Regular.ttf")?;
+
+    let dialogue = TextLayout::new(
+        &font,
+        "The [color=#ff0]old lighthouse[/color] has been dark for years. \
+         Bring it [size=24]back[/size] and the ships will find their way home again.",
+        TextStyle::new(18.0, Pixel::WHITE)
+            .with_line_height(1.2)
+            .with_align(TextAlign::Justified, VerticalAlign::Top),
+    )
+    .with_max_width(580.0);
+
+    let mut score: u32 = 0;
+
+    while !window.closed() && !window.key_down(Key::Escape) {
+        window.update();
+
+        let mut scene = renderer.begin_scene(&window);
+        scene.clear(Pixel::rgb(0x1f, 0x1f, 0xdf));
+
+        scene.draw_text(
+            &font,
+            &format!("Score: {score}"),
+            [10.0, 10.0],
+            24.0,
+            Pixel::WHITE,
+        );
+
+        scene.draw_filled_rounded_rect([170.0, 140.0], [470.0, 340.0], [8.0; 4], Pixel::BLACK);
+        scene.draw_text_box(
+            &font,
+            "New game\nContinue\nOptions\nQuit",
+            [170.0, 140.0],
+            [470.0, 340.0],
+            TextStyle::new(32.0, Pixel::YELLOW)
+                .with_line_height(1.25)
+                .with_align(TextAlign::Center, VerticalAlign::Middle),
+        );
+
+        scene.draw_text_box(
+            &font,
+            "AVATAR Wave Type",
+            [630.0, 470.0],
+            [630.0, 470.0],
+            TextStyle::new(14.0, Pixel::WHITE).with_align(TextAlign::Right, VerticalAlign::Bottom),
+        );
+
+        scene.draw_filled_rounded_rect([20.0, 355.0], [620.0, 445.0], [8.0; 4], Pixel::BLACK);
+        scene.draw_text_layout_revealed(&dialogue, [30.0, 365.0], score as usize / 2);
+
+        renderer.end_scene(scene, &mut gpu);
+
+        score = (score + 1) % 100_000;
+    }
+
+    Ok(())
+}
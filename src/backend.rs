@@ -0,0 +1,185 @@
+use std::{any::Any, ops::Range};
+
+use crate::{geometry::Vertex, Bitmap, Pixel, RenderStats, SdfVertex, SglError, Texture, View};
+
+/// Executes the render commands produced from a [`Scene`](crate::Scene).
+///
+/// [`GraphicsDevice`](crate::GraphicsDevice) renders with wgpu, [`RecordingBackend`](crate::RecordingBackend)
+/// keeps the commands around for inspection.
+pub trait Backend {
+    /// Create the backend resources for a texture. The returned value is owned by the
+    /// [`Texture`] and can be retrieved again with [`Texture::raw`].
+    fn create_texture(
+        &self,
+        width: u32,
+        height: u32,
+        label: Option<&str>,
+    ) -> Box<dyn Any + Send + Sync>;
+
+    /// Copy the bitmap into the texture. The bitmap has already been checked to match the
+    /// texture dimensions.
+    fn upload_texture(&self, texture: &Texture, bitmap: &Bitmap) -> Result<(), SglError>;
+
+    /// Copy the `source` area of the bitmap, given as `(x, y, width, height)`, into the texture
+    /// at `x`, `y`. The area has already been checked to be non-empty and to fit both.
+    fn upload_texture_region(
+        &self,
+        texture: &Texture,
+        x: u32,
+        y: u32,
+        bitmap: &Bitmap,
+        source: (u32, u32, u32, u32),
+    ) -> Result<(), SglError>;
+
+    /// Read the texture back into a bitmap of its size.
+    fn download_texture(&self, texture: &Texture) -> Result<Bitmap, SglError>;
+
+    /// Execute the render commands for a frame.
+    fn submit(&mut self, render_commands: RenderCommands) -> RenderStats;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    TriangleList,
+    TriangleStrip,
+}
+
+/// Backend agnostic description of a frame. Every draw indexes into the shared vertex and index
+/// lists, with indices relative to the start of its vertex range. Anti-aliased shapes have their
+/// own vertex list but share the index list.
+#[derive(Debug)]
+pub struct RenderCommands<'draw> {
+    pub clear_color: Option<Pixel>,
+    pub vertices: Vec<Vertex>,
+    pub sdf_vertices: Vec<SdfVertex>,
+    pub indices: Vec<u32>,
+    pub commands: Vec<RenderCommand<'draw>>,
+}
+
+#[derive(Debug)]
+pub enum RenderCommand<'draw> {
+    Draw {
+        topology: Topology,
+        texture: &'draw Texture,
+        vertices: Range<u32>,
+        indices: Range<u32>,
+    },
+    /// Triangles whose shape is cut out by the signed distance shader, see [`SdfVertex`].
+    DrawSdf {
+        vertices: Range<u32>,
+        indices: Range<u32>,
+    },
+    View(View),
+}
+
+impl<'draw> RenderCommands<'draw> {
+    pub(crate) fn new(clear_color: Option<Pixel>) -> Self {
+        Self {
+            clear_color,
+            vertices: Vec::new(),
+            sdf_vertices: Vec::new(),
+            indices: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push_draw(
+        &mut self,
+        topology: Topology,
+        texture: &'draw Texture,
+        vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+    ) {
+        if indices.is_empty() {
+            return;
+        }
+
+        let vertices_start = self.vertices.len() as u32;
+        let indices_start = self.indices.len() as u32;
+
+        self.vertices.extend(vertices);
+        self.indices.extend(indices);
+
+        self.commands.push(RenderCommand::Draw {
+            topology,
+            texture,
+            vertices: vertices_start..self.vertices.len() as u32,
+            indices: indices_start..self.indices.len() as u32,
+        });
+    }
+
+    pub(crate) fn push_sdf_draw(&mut self, vertices: Vec<SdfVertex>, indices: Vec<u32>) {
+        if indices.is_empty() {
+            return;
+        }
+
+        let vertices_start = self.sdf_vertices.len() as u32;
+        let indices_start = self.indices.len() as u32;
+
+        self.sdf_vertices.extend(vertices);
+        self.indices.extend(indices);
+
+        self.commands.push(RenderCommand::DrawSdf {
+            vertices: vertices_start..self.sdf_vertices.len() as u32,
+            indices: indices_start..self.indices.len() as u32,
+        });
+    }
+
+    pub(crate) fn push_view(&mut self, view: View) {
+        self.commands.push(RenderCommand::View(view));
+    }
+
+    /// The counts a backend would see if it only changes state when it differs from what is
+    /// already bound. Upload sizes and timings are left for the backend to fill in.
+    pub fn stats(&self) -> RenderStats {
+        let mut stats = RenderStats {
+            vertices: (self.vertices.len() + self.sdf_vertices.len()) as u32,
+            ..Default::default()
+        };
+
+        // The topology of the bound pipeline, or `None` for the signed distance one.
+        let mut current_topology = None;
+        let mut current_texture: Option<&Texture> = None;
+
+        for command in self.commands.iter() {
+            match command {
+                RenderCommand::Draw {
+                    topology,
+                    texture,
+                    indices,
+                    ..
+                } => {
+                    if current_topology != Some(Some(*topology)) {
+                        current_topology = Some(Some(*topology));
+                        stats.pipeline_switches += 1;
+                    }
+
+                    if !current_texture.is_some_and(|t| std::ptr::eq(t, *texture)) {
+                        current_texture = Some(texture);
+                        stats.bind_group_switches += 1;
+                    }
+
+                    stats.draw_calls += 1;
+                    stats.indices += indices.end - indices.start;
+                }
+
+                RenderCommand::DrawSdf { indices, .. } => {
+                    if current_topology != Some(None) {
+                        current_topology = Some(None);
+                        stats.pipeline_switches += 1;
+                    }
+
+                    stats.draw_calls += 1;
+                    stats.indices += indices.end - indices.start;
+                }
+
+                RenderCommand::View(_) => {
+                    stats.views += 1;
+                    stats.bind_group_switches += 1;
+                }
+            }
+        }
+
+        stats
+    }
+}
@@ -25,7 +25,7 @@ impl Bitmap {
     where
         P: IntoIterator<Item = Pixel>,
     {
-        let pixels = Vec::from_iter(pixels.into_iter());
+        let pixels = Vec::from_iter(pixels);
 
         if width as usize * height as usize != pixels.len() {
             return Err(SglError::General(format!(
@@ -0,0 +1,696 @@
+use std::{collections::HashMap, fmt};
+
+#[cfg(feature = "image")]
+use std::path::Path;
+
+use sgl_math::{v2, Transform, Vec2};
+
+#[cfg(feature = "image")]
+use crate::load_file;
+use crate::{
+    geometry::Vertex, shape::RectangleShape, text::PlacedGlyph, Backend, Bitmap, SglError,
+    TextStyle, Texture,
+};
+
+/// More pages than any font needs, to catch broken page ids before allocating for them.
+const MAX_PAGES: usize = 256;
+
+/// A font drawn from glyphs in textures, for authored pixel art fonts.
+///
+/// Fonts come from AngelCode BMFont `.fnt` files, in the text or binary format, or from a sheet
+/// with every glyph in a cell of the same size. Glyphs are drawn as textured quads, so text
+/// drawn at a whole multiple of [`BitmapFont::size`] keeps its pixels crisp.
+pub struct BitmapFont {
+    pages: Vec<Texture>,
+    glyphs: HashMap<char, BitmapGlyph>,
+    kernings: HashMap<(char, char), f32>,
+    size: f32,
+    line_height: f32,
+    /// Distance from the top of a line to the baseline.
+    base: f32,
+}
+
+/// Where a glyph is on its page and how to place it, in texture pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BitmapGlyph {
+    page: usize,
+    from: Vec2,
+    to: Vec2,
+    /// From the pen position at the top of the line to the top-left of the glyph.
+    offset: Vec2,
+    advance: f32,
+}
+
+/// The contents of a `.fnt` file, before its pages are loaded.
+#[derive(Debug, Default, PartialEq)]
+struct FntDescription {
+    size: f32,
+    line_height: f32,
+    base: f32,
+    pages: Vec<String>,
+    glyphs: HashMap<char, BitmapGlyph>,
+    kernings: HashMap<(char, char), f32>,
+}
+
+impl BitmapFont {
+    /// Load a BMFont from the contents of its `.fnt` file and its pages, in page id order.
+    pub fn from_fnt(gpu: &impl Backend, fnt: &[u8], pages: &[Bitmap]) -> Result<Self, SglError> {
+        let description = FntDescription::parse(fnt)?;
+
+        if pages.len() < description.pages.len() {
+            return Err(SglError::General(format!(
+                "font has {} pages but {} were given",
+                description.pages.len(),
+                pages.len()
+            )));
+        }
+
+        Ok(Self {
+            pages: upload_pages(gpu, pages)?,
+            glyphs: description.glyphs,
+            kernings: description.kernings,
+            size: description.size,
+            line_height: description.line_height,
+            base: description.base,
+        })
+    }
+
+    /// Load a BMFont `.fnt` file, along with the page images it names next to it.
+    #[cfg(feature = "image")]
+    pub fn load_file<P>(gpu: &impl Backend, path: P) -> Result<Self, SglError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let fnt = load_file(path)?;
+        let description = FntDescription::parse(&fnt)?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let pages = description
+            .pages
+            .iter()
+            .map(|page| Bitmap::from_image_bytes(&load_file(directory.join(page))?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_fnt(gpu, &fnt, &pages)
+    }
+
+    /// Slice a sheet into cells of `cell_width` by `cell_height`, holding the glyphs of `chars`
+    /// from left to right and top to bottom. Every glyph advances by the width of a cell.
+    pub fn from_grid(
+        gpu: &impl Backend,
+        bitmap: &Bitmap,
+        cell_width: u32,
+        cell_height: u32,
+        chars: &str,
+    ) -> Result<Self, SglError> {
+        let columns = bitmap.width() / cell_width.max(1);
+        let rows = bitmap.height() / cell_height.max(1);
+        let count = chars.chars().count();
+
+        if cell_width == 0 || cell_height == 0 || count > (columns * rows) as usize {
+            return Err(SglError::General(format!(
+                "{count} glyphs don't fit in a {columns} by {rows} grid"
+            )));
+        }
+
+        let cell = v2(cell_width as f32, cell_height as f32);
+        let glyphs = chars
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let from = v2((i as u32 % columns) as f32, (i as u32 / columns) as f32) * cell;
+                let glyph = BitmapGlyph {
+                    page: 0,
+                    from,
+                    to: from + cell,
+                    offset: v2(0.0, 0.0),
+                    advance: cell.x,
+                };
+
+                (c, glyph)
+            })
+            .collect();
+
+        Ok(Self {
+            pages: upload_pages(gpu, std::slice::from_ref(bitmap))?,
+            glyphs,
+            kernings: HashMap::new(),
+            size: cell.y,
+            line_height: cell.y,
+            base: cell.y,
+        })
+    }
+
+    /// The size the font was made at. Text drawn at whole multiples of it stays crisp.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// Distance between lines of text at `size`.
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.line_height * size / self.size
+    }
+
+    /// Distance from the top of a line of text at `size` to its baseline.
+    pub(crate) fn ascent(&self, size: f32) -> f32 {
+        self.base * size / self.size
+    }
+
+    /// How far the pen moves for `c` at `size`, or `None` when the font doesn't have it.
+    pub(crate) fn advance(&self, c: char, size: f32) -> Option<f32> {
+        self.glyphs
+            .get(&c)
+            .map(|glyph| glyph.advance * size / self.size)
+    }
+
+    /// Adjustment between the advance of `first` and `second` at `size`.
+    pub(crate) fn kerning(&self, first: char, second: char, size: f32) -> f32 {
+        self.kernings.get(&(first, second)).copied().unwrap_or(0.0) * size / self.size
+    }
+
+    /// Textured triangle lists for `text` laid out in the box from `from` to `to`, one for each
+    /// page glyphs are used from.
+    pub(crate) fn text_geometry(
+        &self,
+        text: &str,
+        from: Vec2,
+        to: Vec2,
+        style: &TextStyle,
+        transform: Transform,
+    ) -> Vec<(&Texture, Vec<Vertex>, Vec<u32>)> {
+        if self.size <= 0.0 {
+            return Vec::new();
+        }
+
+        let scale = style.size / self.size;
+        let line_advance = self.line_height * scale * style.line_height;
+
+        let lines: Vec<&str> = text.split('\n').collect();
+        let top = style.block_top(from, to, line_advance * lines.len() as f32);
+
+        let mut glyphs = Vec::new();
+
+        for (i, line) in lines.iter().enumerate() {
+            let mut pen = v2(
+                style.line_left(from, to, self.line_width(line) * scale),
+                top + line_advance * i as f32 + self.base * scale,
+            );
+
+            self.for_each_glyph(line, |c, glyph, kerning| {
+                pen.x += kerning * scale;
+                glyphs.push(PlacedGlyph {
+                    c,
+                    position: pen,
+                    size: style.size,
+                    color: style.color,
+                });
+                pen.x += glyph.advance * scale;
+            });
+        }
+
+        self.glyphs_geometry(&glyphs, transform)
+    }
+
+    /// Textured triangle lists for glyphs placed with their pen position on the baseline, one
+    /// for each page glyphs are used from.
+    pub(crate) fn glyphs_geometry(
+        &self,
+        glyphs: &[PlacedGlyph],
+        transform: Transform,
+    ) -> Vec<(&Texture, Vec<Vertex>, Vec<u32>)> {
+        let mut pages: Vec<(Vec<Vertex>, Vec<u32>)> = vec![Default::default(); self.pages.len()];
+
+        for placed in glyphs {
+            let Some(glyph) = self.glyphs.get(&placed.c) else {
+                continue;
+            };
+
+            let scale = placed.size / self.size;
+            let size = glyph.to - glyph.from;
+            if self.size <= 0.0 || size.x <= 0.0 || size.y <= 0.0 {
+                continue;
+            }
+
+            let position = placed.position + (glyph.offset - v2(0.0, self.base)) * scale;
+            let rect = RectangleShape::new(position, position + size * scale);
+            let (vertices, indices) = rect.texture_geometry(
+                &self.pages[glyph.page],
+                Some((glyph.from, glyph.to)),
+                placed.color,
+                false,
+                transform,
+            );
+
+            let (page_vertices, page_indices) = &mut pages[glyph.page];
+            let first = page_vertices.len() as u32;
+            page_vertices.extend(vertices);
+            page_indices.extend(indices.into_iter().map(|i| first + i));
+        }
+
+        self.pages
+            .iter()
+            .zip(pages)
+            .filter(|(_, (_, indices))| !indices.is_empty())
+            .map(|(texture, (vertices, indices))| (texture, vertices, indices))
+            .collect()
+    }
+
+    /// Width of a line of text at the font's own size, including kerning.
+    fn line_width(&self, line: &str) -> f32 {
+        let mut width = 0.0;
+        self.for_each_glyph(line, |_, glyph, kerning| width += kerning + glyph.advance);
+
+        width
+    }
+
+    /// Call `f` with each glyph in `line` the font has, and the kerning between it and the one
+    /// before.
+    fn for_each_glyph(&self, line: &str, mut f: impl FnMut(char, &BitmapGlyph, f32)) {
+        let mut previous = None;
+
+        for c in line.chars() {
+            let Some(glyph) = self.glyphs.get(&c) else {
+                continue;
+            };
+
+            let kerning = previous
+                .and_then(|previous| self.kernings.get(&(previous, c)))
+                .copied()
+                .unwrap_or(0.0);
+            previous = Some(c);
+
+            f(c, glyph, kerning);
+        }
+    }
+}
+
+impl fmt::Debug for BitmapFont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitmapFont")
+            .field("pages", &self.pages)
+            .field("glyphs", &self.glyphs.len())
+            .field("size", &self.size)
+            .field("line_height", &self.line_height)
+            .finish()
+    }
+}
+
+fn upload_pages(gpu: &impl Backend, pages: &[Bitmap]) -> Result<Vec<Texture>, SglError> {
+    pages
+        .iter()
+        .map(|page| {
+            let texture = Texture::new(
+                page.width(),
+                page.height(),
+                gpu,
+                Some("sgl::bitmap_font::page"),
+            );
+            texture.upload_to_gpu(gpu, page)?;
+
+            Ok(texture)
+        })
+        .collect()
+}
+
+impl FntDescription {
+    const BINARY_MAGIC: &'static [u8] = b"BMF";
+
+    fn parse(bytes: &[u8]) -> Result<Self, SglError> {
+        if bytes.starts_with(Self::BINARY_MAGIC) {
+            Self::parse_binary(bytes)
+        } else {
+            let text = std::str::from_utf8(bytes).map_err(|e| SglError::General(e.to_string()))?;
+            Self::parse_text(text)
+        }
+    }
+
+    /// Lines of a tag followed by `key=value` pairs, where values may be quoted.
+    fn parse_text(text: &str) -> Result<Self, SglError> {
+        let mut description = Self::default();
+
+        for line in text.lines() {
+            let mut rest = line.trim();
+            let tag = take_word(&mut rest);
+
+            let mut values = HashMap::new();
+            while !rest.is_empty() {
+                let Some((key, value)) = rest.split_once('=') else {
+                    break;
+                };
+
+                let value = value.trim_start();
+                let (value, remainder) = match value.strip_prefix('"') {
+                    Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+                    None => value.split_once(char::is_whitespace).unwrap_or((value, "")),
+                };
+
+                values.insert(key.trim(), value);
+                rest = remainder.trim_start();
+            }
+
+            let number = |key: &str| -> Result<f32, SglError> {
+                values
+                    .get(key)
+                    .map_or(Ok(0.0), |value| value.parse::<f32>())
+                    .map_err(|e| SglError::General(format!("bad value for {key} in {tag}: {e}")))
+            };
+
+            match tag {
+                "info" => description.size = number("size")?.abs(),
+                "common" => {
+                    description.line_height = number("lineHeight")?;
+                    description.base = number("base")?;
+                }
+                "page" => {
+                    let id = number("id")?;
+                    if !(0.0..MAX_PAGES as f32).contains(&id) || id.fract() != 0.0 {
+                        return Err(SglError::General(format!("bad page id {id}")));
+                    }
+
+                    let id = id as usize;
+                    let file = values.get("file").copied().unwrap_or_default();
+
+                    if description.pages.len() <= id {
+                        description.pages.resize(id + 1, String::new());
+                    }
+                    description.pages[id] = file.to_string();
+                }
+                "char" => {
+                    let Some(c) = char::from_u32(number("id")? as u32) else {
+                        continue;
+                    };
+
+                    description.add_glyph(
+                        c,
+                        [
+                            number("x")?,
+                            number("y")?,
+                            number("width")?,
+                            number("height")?,
+                            number("xoffset")?,
+                            number("yoffset")?,
+                            number("xadvance")?,
+                        ],
+                        number("page")? as usize,
+                    );
+                }
+                "kerning" => description.add_kerning(
+                    number("first")? as u32,
+                    number("second")? as u32,
+                    number("amount")?,
+                ),
+                _ => {}
+            }
+        }
+
+        description.finish()
+    }
+
+    /// Version 3 of the binary format: blocks of a type byte and a little endian length.
+    fn parse_binary(bytes: &[u8]) -> Result<Self, SglError> {
+        if bytes.get(3) != Some(&3) {
+            return Err(SglError::General(
+                "only version 3 of the binary BMFont format is supported".to_string(),
+            ));
+        }
+
+        let mut description = Self::default();
+        let mut reader = Reader { bytes, position: 4 };
+
+        while reader.position < bytes.len() {
+            let block_type = reader.u8()?;
+            let length = reader.u32()? as usize;
+            let block = reader.take(length)?;
+            let mut block = Reader {
+                bytes: block,
+                position: 0,
+            };
+
+            match block_type {
+                1 => description.size = (block.u16()? as i16).unsigned_abs() as f32,
+                2 => {
+                    description.line_height = block.u16()? as f32;
+                    description.base = block.u16()? as f32;
+                }
+                3 => {
+                    description.pages = block
+                        .bytes
+                        .split(|b| *b == 0)
+                        .filter(|name| !name.is_empty())
+                        .map(|name| String::from_utf8_lossy(name).into_owned())
+                        .collect();
+                }
+                4 => {
+                    while block.position + 20 <= block.bytes.len() {
+                        let id = block.u32()?;
+                        let x = block.u16()? as f32;
+                        let y = block.u16()? as f32;
+                        let width = block.u16()? as f32;
+                        let height = block.u16()? as f32;
+                        let x_offset = block.u16()? as i16 as f32;
+                        let y_offset = block.u16()? as i16 as f32;
+                        let advance = block.u16()? as i16 as f32;
+                        let page = block.u8()? as usize;
+                        block.u8()?;
+
+                        if let Some(c) = char::from_u32(id) {
+                            description.add_glyph(
+                                c,
+                                [x, y, width, height, x_offset, y_offset, advance],
+                                page,
+                            );
+                        }
+                    }
+                }
+                5 => {
+                    while block.position + 10 <= block.bytes.len() {
+                        let first = block.u32()?;
+                        let second = block.u32()?;
+                        let amount = block.u16()? as i16 as f32;
+
+                        description.add_kerning(first, second, amount);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        description.finish()
+    }
+
+    fn add_glyph(&mut self, c: char, values: [f32; 7], page: usize) {
+        let [x, y, width, height, x_offset, y_offset, advance] = values;
+
+        self.glyphs.insert(
+            c,
+            BitmapGlyph {
+                page,
+                from: v2(x, y),
+                to: v2(x + width, y + height),
+                offset: v2(x_offset, y_offset),
+                advance,
+            },
+        );
+    }
+
+    fn add_kerning(&mut self, first: u32, second: u32, amount: f32) {
+        if let (Some(first), Some(second)) = (char::from_u32(first), char::from_u32(second)) {
+            self.kernings.insert((first, second), amount);
+        }
+    }
+
+    fn finish(mut self) -> Result<Self, SglError> {
+        if let Some(glyph) = self.glyphs.values().find(|g| g.page >= self.pages.len()) {
+            return Err(SglError::General(format!(
+                "glyph on page {} but the font has {} pages",
+                glyph.page,
+                self.pages.len()
+            )));
+        }
+
+        // Some generators leave the size out, the line height is the next best thing.
+        if self.size == 0.0 {
+            self.size = self.line_height;
+        }
+
+        Ok(self)
+    }
+}
+
+fn take_word<'a>(text: &mut &'a str) -> &'a str {
+    let (word, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    *text = rest.trim_start();
+
+    word
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], SglError> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + length)
+            .ok_or_else(|| SglError::General("BMFont file ends early".to_string()))?;
+        self.position += length;
+
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, SglError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, SglError> {
+        Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    fn u32(&mut self) -> Result<u32, SglError> {
+        Ok(u32::from_le_bytes([
+            self.u8()?,
+            self.u8()?,
+            self.u8()?,
+            self.u8()?,
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pixel, RecordingBackend};
+
+    static TEXT_FNT: &str = r#"info face="Tiny Font" size=-8 bold=0 italic=0 padding=0,0,0,0
+common lineHeight=10 base=7 scaleW=64 scaleH=64 pages=1 packed=0
+page id=0 file="tiny_0.png"
+chars count=2
+char id=65   x=0  y=0  width=5  height=7  xoffset=0  yoffset=1  xadvance=6  page=0 chnl=15
+char id=86   x=6  y=0  width=5  height=7  xoffset=-1 yoffset=1  xadvance=5  page=0 chnl=15
+kernings count=1
+kerning first=65 second=86 amount=-1
+"#;
+
+    fn binary_fnt() -> Vec<u8> {
+        let mut bytes = b"BMF\x03".to_vec();
+
+        let mut block = |block_type: u8, contents: &[u8]| {
+            bytes.push(block_type);
+            bytes.extend((contents.len() as u32).to_le_bytes());
+            bytes.extend(contents);
+        };
+
+        let mut info = (-8i16).to_le_bytes().to_vec();
+        info.extend([0; 12]);
+        info.extend(b"Tiny Font\0");
+        block(1, &info);
+        block(2, &[10, 0, 7, 0, 64, 0, 64, 0, 1, 0, 0, 0, 0, 0, 0]);
+        block(3, b"tiny_0.png\0");
+
+        let mut chars = Vec::new();
+        for (id, x, x_offset, advance) in [(65u32, 0u16, 0i16, 6i16), (86, 6, -1, 5)] {
+            chars.extend(id.to_le_bytes());
+            for value in [x, 0, 5, 7] {
+                chars.extend(value.to_le_bytes());
+            }
+            for value in [x_offset, 1, advance] {
+                chars.extend(value.to_le_bytes());
+            }
+            chars.extend([0, 15]);
+        }
+        block(4, &chars);
+
+        let mut kernings = 65u32.to_le_bytes().to_vec();
+        kernings.extend(86u32.to_le_bytes());
+        kernings.extend((-1i16).to_le_bytes());
+        block(5, &kernings);
+
+        bytes
+    }
+
+    #[test]
+    fn text_and_binary_fnt_match() {
+        let text = FntDescription::parse(TEXT_FNT.as_bytes()).unwrap();
+        let binary = FntDescription::parse(&binary_fnt()).unwrap();
+
+        assert_eq!(text, binary);
+        assert_eq!(8.0, text.size);
+        assert_eq!(10.0, text.line_height);
+        assert_eq!(7.0, text.base);
+        assert_eq!(vec!["tiny_0.png".to_string()], text.pages);
+        assert_eq!(
+            BitmapGlyph {
+                page: 0,
+                from: v2(6.0, 0.0),
+                to: v2(11.0, 7.0),
+                offset: v2(-1.0, 1.0),
+                advance: 5.0,
+            },
+            text.glyphs[&'V']
+        );
+        assert_eq!(-1.0, text.kernings[&('A', 'V')]);
+    }
+
+    #[test]
+    fn missing_pages_are_an_error() {
+        let backend = RecordingBackend::new();
+
+        assert!(BitmapFont::from_fnt(&backend, TEXT_FNT.as_bytes(), &[]).is_err());
+        assert!(FntDescription::parse(&binary_fnt()[..20]).is_err());
+    }
+
+    #[test]
+    fn page_ids_must_be_small_whole_numbers() {
+        for id in ["1e30", "2000000000", "-1", "1.5", "NaN", "256"] {
+            let fnt = format!("common lineHeight=10 base=7 pages=1\npage id={id} file=\"a.png\"\n");
+            assert!(FntDescription::parse(fnt.as_bytes()).is_err(), "{id}");
+        }
+
+        let fnt = "common lineHeight=10 base=7 pages=2\npage id=1 file=\"b.png\"\n";
+        let description = FntDescription::parse(fnt.as_bytes()).unwrap();
+        assert_eq!(vec![String::new(), "b.png".to_string()], description.pages);
+    }
+
+    #[test]
+    fn glyphs_are_scaled_and_kerned() {
+        let backend = RecordingBackend::new();
+        let font =
+            BitmapFont::from_fnt(&backend, TEXT_FNT.as_bytes(), &[Bitmap::new(64, 64)]).unwrap();
+
+        let style = TextStyle::new(16.0, Pixel::WHITE);
+        let draws = font.text_geometry(
+            "AV",
+            v2(0.0, 0.0),
+            v2(0.0, 0.0),
+            &style,
+            Transform::IDENTITY,
+        );
+
+        assert_eq!(1, draws.len());
+        let (_, vertices, indices) = &draws[0];
+        assert_eq!(8, vertices.len());
+        assert_eq!(12, indices.len());
+
+        // A at 0, then V after A's advance of 12, kerning of -2 and its offset of -2.
+        assert_eq!([0.0, 2.0], vertices[0].coords);
+        assert_eq!([8.0, 2.0], vertices[4].coords);
+        assert_eq!([18.0, 16.0], vertices[6].coords);
+        assert_eq!(20.0, font.line_height(16.0));
+    }
+
+    #[test]
+    fn grid_font_is_sliced_in_reading_order() {
+        let backend = RecordingBackend::new();
+        let sheet = Bitmap::new(16, 16);
+        let font = BitmapFont::from_grid(&backend, &sheet, 8, 8, "abc").unwrap();
+
+        assert_eq!(8.0, font.size());
+        assert_eq!(v2(0.0, 8.0), font.glyphs[&'c'].from);
+        assert_eq!(v2(8.0, 16.0), font.glyphs[&'c'].to);
+
+        assert!(BitmapFont::from_grid(&backend, &sheet, 8, 8, "abcde").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use mint::Vector2;
+use sgl_math::{v2, Aabb, Vec2};
+
+use crate::View;
+
+/// How a [`Camera`] catches up with its target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Follow {
+    /// Jump to the target right away.
+    Instant,
+    /// Close the same fraction of the distance every moment, at `rate` per second.
+    Lerp(f32),
+    /// Move like a critically damped spring of angular `frequency`, easing in and out without
+    /// overshooting.
+    Spring(f32),
+}
+
+/// A camera moving a [`View`] after a target, with smoothing, a deadzone, world bounds, pixel
+/// snapping and screen shake.
+///
+/// Call [`Camera::update`] once a frame and draw with [`Camera::view`]. The size, zoom and
+/// rotation of the view can be changed through [`Camera::view_mut`], the camera takes care of its
+/// center.
+#[derive(Debug, Clone)]
+pub struct Camera {
+    view: View,
+    position: Vec2,
+    velocity: Vec2,
+    target: Vec2,
+    follow: Follow,
+    deadzone: Option<Vec2>,
+    bounds: Option<Aabb>,
+    pixel_size: Option<Vec2>,
+    shake: Shake,
+    trauma: f32,
+    time: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Shake {
+    max_offset: f32,
+    max_angle: f32,
+    frequency: f32,
+    decay: f32,
+}
+
+impl Camera {
+    pub fn new(view: View) -> Self {
+        Self {
+            view,
+            position: view.center(),
+            velocity: v2(0.0, 0.0),
+            target: view.center(),
+            follow: Follow::Instant,
+            deadzone: None,
+            bounds: None,
+            pixel_size: None,
+            shake: Shake {
+                max_offset: 16.0,
+                max_angle: 0.1,
+                frequency: 15.0,
+                decay: 1.0,
+            },
+            trauma: 0.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_follow(mut self, follow: Follow) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    /// Let the target move inside a `width` by `height` area around the center before the
+    /// camera follows.
+    pub fn with_deadzone(mut self, width: f32, height: f32) -> Self {
+        self.deadzone = Some(v2(width.abs() / 2.0, height.abs() / 2.0));
+        self
+    }
+
+    /// Keep the view inside `bounds`, or centered on them when they are smaller than the view.
+    pub fn with_bounds(mut self, bounds: Aabb) -> Self {
+        self.bounds = Some(bounds);
+        self.position = self.clamped(self.position);
+        self
+    }
+
+    /// Round the center of the view to whole screen pixels of a window with `pixel_size`, so
+    /// pixel art doesn't shimmer as the camera moves.
+    pub fn with_pixel_snap<V>(mut self, pixel_size: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.pixel_size = Some(pixel_size.into().into());
+        self
+    }
+
+    /// Shake by up to `max_offset` world units and `max_angle` radians at full trauma, changing
+    /// direction about `frequency` times a second.
+    pub fn with_shake(mut self, max_offset: f32, max_angle: f32, frequency: f32) -> Self {
+        self.shake.max_offset = max_offset;
+        self.shake.max_angle = max_angle;
+        self.shake.frequency = frequency;
+        self
+    }
+
+    /// Lose `decay` trauma per second. Full trauma lasts a second by default.
+    pub fn with_trauma_decay(mut self, decay: f32) -> Self {
+        self.shake.decay = decay;
+        self
+    }
+
+    /// The view to draw with, shaken and snapped.
+    pub fn view(&self) -> View {
+        let shake = self.trauma * self.trauma;
+        let offset = v2(noise(self.time, 0), noise(self.time, 1)) * self.shake.max_offset * shake;
+        let angle = noise(self.time, 2) * self.shake.max_angle * shake;
+
+        let mut view = self.view;
+        view.set_center(self.snapped(self.position + offset));
+        view.set_rotation(self.view.rotation() + angle);
+
+        view
+    }
+
+    pub fn view_mut(&mut self) -> &mut View {
+        &mut self.view
+    }
+
+    /// Center of the view, before shaking and snapping.
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// Move to `position` and target it right away.
+    pub fn set_position<V>(&mut self, position: V)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.target = position.into().into();
+        self.position = self.clamped(self.target);
+        self.velocity = v2(0.0, 0.0);
+    }
+
+    pub fn target(&self) -> Vec2 {
+        self.target
+    }
+
+    /// Follow `target` from the next update on.
+    pub fn follow<V>(&mut self, target: V)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.target = target.into().into();
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Add to the trauma causing the shake, up to 1. The shake grows with the square of trauma,
+    /// so small hits barely move the view while big ones throw it around.
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    /// Move towards the target and calm the shake down, `dt` seconds after the last update.
+    pub fn update(&mut self, dt: f32) {
+        let goal = self.clamped(self.deadzone_goal());
+
+        match self.follow {
+            Follow::Instant => {
+                self.position = goal;
+                self.velocity = v2(0.0, 0.0);
+            }
+            Follow::Lerp(rate) => {
+                self.position += (goal - self.position) * (1.0 - (-rate * dt).exp());
+            }
+            Follow::Spring(frequency) => {
+                let offset = self.position - goal;
+                let decay = (-frequency * dt).exp();
+                let change = (self.velocity + offset * frequency) * dt;
+
+                self.velocity = (self.velocity - change * frequency) * decay;
+                self.position = goal + (offset + change) * decay;
+            }
+        }
+
+        self.position = self.clamped(self.position);
+        self.trauma = (self.trauma - self.shake.decay * dt).max(0.0);
+        self.time += dt * self.shake.frequency;
+    }
+
+    /// Where the center has to be for the target to be inside the deadzone.
+    fn deadzone_goal(&self) -> Vec2 {
+        let Some(half) = self.deadzone else {
+            return self.target;
+        };
+
+        let axis =
+            |position: f32, target: f32, half: f32| position.clamp(target - half, target + half);
+
+        v2(
+            axis(self.position.x, self.target.x, half.x),
+            axis(self.position.y, self.target.y, half.y),
+        )
+    }
+
+    fn clamped(&self, center: Vec2) -> Vec2 {
+        let Some(bounds) = self.bounds else {
+            return center;
+        };
+
+        let axis = |center: f32, min: f32, max: f32, half: f32| {
+            if max - min <= half * 2.0 {
+                (min + max) / 2.0
+            } else {
+                center.clamp(min + half, max - half)
+            }
+        };
+
+        let (min, max) = (bounds.min(), bounds.max());
+        v2(
+            axis(center.x, min.x, max.x, self.view.width() / 2.0),
+            axis(center.y, min.y, max.y, self.view.height() / 2.0),
+        )
+    }
+
+    fn snapped(&self, center: Vec2) -> Vec2 {
+        let Some(pixel_size) = self.pixel_size else {
+            return center;
+        };
+
+        let step = pixel_size * self.view.zoom();
+        v2(
+            (center.x * step.x).round() / step.x,
+            (center.y * step.y).round() / step.y,
+        )
+    }
+}
+
+/// Smooth noise between -1 and 1, changing direction about once every unit of `time`. `seed`
+/// gives independent noise for each axis.
+fn noise(time: f32, seed: u32) -> f32 {
+    let random = |i: i32| {
+        let mut x = (i as u32).wrapping_mul(0x9e37_79b9) ^ seed.wrapping_mul(0x85eb_ca6b);
+        x ^= x >> 15;
+        x = x.wrapping_mul(0x2c1b_3c6d);
+        x ^= x >> 12;
+
+        (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+    };
+
+    let (i, t) = (time.floor(), time.fract());
+    let smooth = t * t * (3.0 - 2.0 * t);
+
+    random(i as i32) + (random(i as i32 + 1) - random(i as i32)) * smooth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> Camera {
+        Camera::new(View::new([0.0, 0.0], 320.0, 240.0))
+    }
+
+    #[test]
+    fn lerp_closes_a_fraction_of_the_distance() {
+        let mut camera = camera().with_follow(Follow::Lerp(2.0_f32.ln()));
+        camera.follow([100.0, 0.0]);
+        camera.update(1.0);
+
+        assert!((camera.position() - v2(50.0, 0.0)).len() < 1e-3);
+    }
+
+    #[test]
+    fn spring_settles_without_overshooting() {
+        let mut camera = camera().with_follow(Follow::Spring(8.0));
+        camera.follow([100.0, -50.0]);
+
+        let mut previous = camera.position();
+        for _ in 0..120 {
+            camera.update(1.0 / 60.0);
+            assert!(camera.position().x >= previous.x && camera.position().x <= 100.0);
+            previous = camera.position();
+        }
+
+        assert!((camera.position() - v2(100.0, -50.0)).len() < 0.1);
+    }
+
+    #[test]
+    fn deadzone_holds_until_target_leaves_it() {
+        let mut camera = camera().with_deadzone(40.0, 20.0);
+
+        camera.follow([15.0, -5.0]);
+        camera.update(0.1);
+        assert_eq!(v2(0.0, 0.0), camera.position());
+
+        camera.follow([30.0, -25.0]);
+        camera.update(0.1);
+        assert_eq!(v2(10.0, -15.0), camera.position());
+    }
+
+    #[test]
+    fn bounds_keep_the_view_inside() {
+        let mut camera = camera().with_bounds(Aabb::new(v2(0.0, 0.0), v2(1000.0, 200.0)));
+
+        camera.follow([-50.0, 100.0]);
+        camera.update(0.1);
+        assert_eq!(v2(160.0, 100.0), camera.position());
+
+        camera.follow([2000.0, 0.0]);
+        camera.update(0.1);
+        assert_eq!(v2(840.0, 100.0), camera.position());
+    }
+
+    #[test]
+    fn view_is_snapped_to_screen_pixels() {
+        let mut camera = camera().with_pixel_snap([2.0, 2.0]);
+        camera.set_position([10.3, 20.8]);
+
+        assert_eq!(v2(10.5, 21.0), camera.view().center());
+
+        camera.view_mut().set_zoom(2.0);
+        assert_eq!(v2(10.25, 20.75), camera.view().center());
+    }
+
+    #[test]
+    fn trauma_shakes_and_wears_off() {
+        let mut camera = camera().with_shake(10.0, 0.2, 20.0);
+        camera.add_trauma(0.5);
+        camera.add_trauma(0.8);
+        assert_eq!(1.0, camera.trauma());
+
+        let mut shaken = false;
+        for _ in 0..10 {
+            camera.update(0.05);
+            let view = camera.view();
+            let offset = view.center().len();
+
+            assert!(offset <= 10.0 * 2.0_f32.sqrt() && view.rotation().abs() <= 0.2);
+            shaken |= offset > 0.0;
+        }
+        assert!(shaken);
+
+        camera.update(1.0);
+        assert_eq!(0.0, camera.trauma());
+        assert_eq!(v2(0.0, 0.0), camera.view().center());
+        assert_eq!(0.0, camera.view().rotation());
+    }
+
+    #[test]
+    fn noise_is_smooth_and_bounded() {
+        for i in 0..1000 {
+            let time = i as f32 * 0.01;
+            let value = noise(time, 0);
+
+            assert!((-1.0..=1.0).contains(&value));
+            assert!((noise(time + 0.001, 0) - value).abs() < 0.01);
+        }
+    }
+}
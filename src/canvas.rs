@@ -0,0 +1,190 @@
+use std::{cell::Cell, fmt};
+
+use crate::{Backend, Bitmap, Pixel, SglError, Texture, Window};
+
+/// A bitmap the size of the window, changed pixel by pixel on the CPU and drawn over the whole
+/// window with [`crate::Scene::draw_canvas`].
+///
+/// Only the area changed since the last upload is sent to the GPU, so touching a few pixels a
+/// frame stays cheap.
+pub struct Canvas {
+    bitmap: Bitmap,
+    texture: Texture,
+    /// Changed area as `(left, top, right, bottom)`, right and bottom exclusive.
+    dirty: Cell<Option<(u32, u32, u32, u32)>>,
+}
+
+impl Canvas {
+    /// A black canvas matching the size given to [`Window::new`].
+    pub fn new(gpu: &impl Backend, window: &Window) -> Self {
+        Self::with_size(gpu, window.size.x as u32, window.size.y as u32)
+    }
+
+    pub(crate) fn with_size(gpu: &impl Backend, width: u32, height: u32) -> Self {
+        let bitmap =
+            Bitmap::from_pixels(width, height, vec![Pixel::BLACK; (width * height) as usize])
+                .expect("width and height must equal pixels length");
+
+        Self {
+            texture: Texture::new(width, height, gpu, Some("sgl::canvas")),
+            bitmap,
+            dirty: Cell::new(Some((0, 0, width, height))),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.bitmap.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.bitmap.height()
+    }
+
+    pub fn bitmap(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    /// The texture the canvas is uploaded to, as of the last upload.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Pixel> {
+        (x < self.width() && y < self.height())
+            .then(|| self.bitmap.pixels()[(y * self.width() + x) as usize])
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Pixel] {
+        self.mark_dirty(0, 0, self.width(), self.height());
+        self.bitmap.pixels_mut()
+    }
+
+    /// Set the pixel at `x`, `y`, if it is on the canvas.
+    pub fn set_pixel(&mut self, x: u32, y: u32, pixel: Pixel) {
+        if x < self.width() && y < self.height() {
+            self.bitmap.set_pixel(x, y, pixel);
+            self.mark_dirty(x, y, x + 1, y + 1);
+        }
+    }
+
+    pub fn clear(&mut self, pixel: Pixel) {
+        self.pixels_mut().fill(pixel);
+    }
+
+    /// Copy `bitmap` onto the canvas with its top left corner at `x`, `y`, replacing what was
+    /// there. Parts falling off the canvas are left out.
+    pub fn blit(&mut self, bitmap: &Bitmap, x: i32, y: i32) {
+        let left = x.max(0);
+        let top = y.max(0);
+        let right = x
+            .saturating_add(bitmap.width() as i32)
+            .min(self.width() as i32);
+        let bottom = y
+            .saturating_add(bitmap.height() as i32)
+            .min(self.height() as i32);
+
+        if left >= right || top >= bottom {
+            return;
+        }
+
+        let width = self.width() as usize;
+        let columns = (left - x) as usize..(right - x) as usize;
+        for row in top..bottom {
+            let source = (row - y) as usize * bitmap.width() as usize;
+            let destination = row as usize * width + left as usize;
+
+            self.bitmap.pixels_mut()[destination..destination + columns.len()]
+                .copy_from_slice(&bitmap.pixels()[source + columns.start..source + columns.end]);
+        }
+
+        self.mark_dirty(left as u32, top as u32, right as u32, bottom as u32);
+    }
+
+    /// Upload the area changed since the last upload. Drawing the canvas in a scene does this
+    /// on its own.
+    pub fn upload_to_gpu(&self, gpu: &impl Backend) -> Result<(), SglError> {
+        if let Some((left, top, right, bottom)) = self.dirty.get() {
+            let area = (left, top, right - left, bottom - top);
+            self.texture
+                .upload_bitmap_region(gpu, left, top, &self.bitmap, area)?;
+            self.dirty.set(None);
+        }
+
+        Ok(())
+    }
+
+    fn mark_dirty(&mut self, left: u32, top: u32, right: u32, bottom: u32) {
+        let dirty = self.dirty.get_mut();
+
+        *dirty = Some(match *dirty {
+            Some((l, t, r, b)) => (l.min(left), t.min(top), r.max(right), b.max(bottom)),
+            None => (left, top, right, bottom),
+        });
+    }
+}
+
+impl fmt::Debug for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Canvas")
+            .field("texture", &self.texture)
+            .field("dirty", &self.dirty.get())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RecordingBackend;
+
+    fn canvas(backend: &RecordingBackend, width: u32, height: u32) -> Canvas {
+        let canvas = Canvas::with_size(backend, width, height);
+        canvas.upload_to_gpu(backend).unwrap();
+        canvas
+    }
+
+    #[test]
+    fn changes_grow_the_dirty_area_until_uploaded() {
+        let backend = RecordingBackend::new();
+        let mut canvas = canvas(&backend, 16, 8);
+        assert_eq!(None, canvas.dirty.get());
+
+        canvas.set_pixel(3, 2, Pixel::RED);
+        assert_eq!(Some((3, 2, 4, 3)), canvas.dirty.get());
+
+        canvas.set_pixel(10, 1, Pixel::RED);
+        canvas.set_pixel(16, 9, Pixel::RED);
+        assert_eq!(Some((3, 1, 11, 3)), canvas.dirty.get());
+        assert_eq!(Some(Pixel::RED), canvas.pixel(10, 1));
+
+        canvas.upload_to_gpu(&backend).unwrap();
+        assert_eq!(None, canvas.dirty.get());
+
+        canvas.clear(Pixel::BLACK);
+        assert_eq!(Some((0, 0, 16, 8)), canvas.dirty.get());
+    }
+
+    #[test]
+    fn blit_is_clipped_to_the_canvas() {
+        let backend = RecordingBackend::new();
+        let mut canvas = canvas(&backend, 4, 4);
+        let pixels = [Pixel::RED, Pixel::GREEN, Pixel::WHITE, Pixel::YELLOW];
+        let bitmap = Bitmap::from_pixels(2, 2, pixels).unwrap();
+
+        canvas.blit(&bitmap, -1, 3);
+
+        assert_eq!(Some((0, 3, 1, 4)), canvas.dirty.get());
+        assert_eq!(Some(Pixel::GREEN), canvas.pixel(0, 3));
+        assert_eq!(Some(Pixel::BLACK), canvas.pixel(1, 3));
+
+        canvas.blit(&bitmap, 4, 0);
+        canvas.blit(&bitmap, i32::MAX, i32::MAX);
+        canvas.blit(&bitmap, 1, 1);
+
+        assert_eq!(Some((0, 1, 3, 4)), canvas.dirty.get());
+        assert_eq!(
+            [Pixel::RED, Pixel::GREEN, Pixel::WHITE, Pixel::YELLOW],
+            [(1, 1), (2, 1), (1, 2), (2, 2)].map(|(x, y)| canvas.pixel(x, y).unwrap())
+        );
+    }
+}
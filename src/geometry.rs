@@ -4,8 +4,12 @@ use bytemuck::{Pod, Zeroable};
 
 use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
 
+/// A corner of the triangles sent to the GPU, as passed to [`Scene::draw_mesh`](crate::Scene::draw_mesh).
+///
+/// `coords` are in scene units, `tex_coords` go from 0 to 1 across the texture, and
+/// `fill_color` is RGBA from 0 to 1, multiplied with the texture.
 #[repr(C)]
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Vertex {
     pub coords: [f32; 2],
     pub tex_coords: [f32; 2],
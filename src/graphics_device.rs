@@ -1,14 +1,43 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    mem::size_of,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
+
+use bytemuck::cast_slice;
 use futures::executor::block_on;
 use wgpu::{
-    util::StagingBelt, Adapter, Backends, CommandEncoder, CommandEncoderDescriptor,
-    CompositeAlphaMode, Device, DeviceDescriptor, Features, Instance, Limits, PowerPreference,
-    PresentMode, Queue, RequestAdapterOptions, Surface, SurfaceConfiguration, SurfaceError,
-    SurfaceTexture, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+    util::StagingBelt, Adapter, AddressMode, Backends, BindGroup, BindGroupDescriptor,
+    BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, BlendState, Buffer, BufferAddress, BufferBinding,
+    BufferBindingType, BufferDescriptor, BufferSize, BufferUsages, Color, ColorTargetState,
+    ColorWrites, CommandEncoder, CommandEncoderDescriptor, CompositeAlphaMode, Device,
+    DeviceDescriptor, DynamicOffset, Extent3d, Features, FilterMode, FragmentState, FrontFace,
+    ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, IndexFormat, Instance, Limits, LoadOp,
+    Maintain, MapMode, MultisampleState, Operations, Origin3d, PipelineLayout,
+    PipelineLayoutDescriptor, PolygonMode, PowerPreference, PresentMode, PrimitiveState,
+    PrimitiveTopology, QuerySet, QuerySetDescriptor, QueryType, Queue, RenderPassColorAttachment,
+    RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor, RequestAdapterOptions, Sampler,
+    SamplerBindingType, SamplerDescriptor, ShaderModule, ShaderModuleDescriptor, ShaderSource,
+    ShaderStages, Surface, SurfaceConfiguration, SurfaceError, SurfaceTexture, TextureAspect,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages,
+    TextureView, TextureViewDescriptor, TextureViewDimension, VertexState,
+    COPY_BYTES_PER_ROW_ALIGNMENT,
 };
 use winit::dpi::PhysicalSize;
 
-use crate::{SglError, Window};
+use crate::{
+    backend::{RenderCommand, RenderCommands, Topology},
+    geometry::Vertex,
+    Backend, Bitmap, Pixel, RenderStats, SdfVertex, SglError, Texture, Upscale, Window,
+};
 
+/// The wgpu [`Backend`].
 pub struct GraphicsDevice {
     pub(crate) _instance: Instance,
     pub(crate) surface: Surface,
@@ -19,10 +48,28 @@ pub struct GraphicsDevice {
     pub(crate) limits: Limits,
     pub(crate) surface_config: SurfaceConfiguration,
     pub(crate) staging_belt: StagingBelt,
+    pub(crate) timer: Option<GpuTimer>,
+    vbo: Buffer,
+    sdf_vbo: Buffer,
+    ibo: Buffer,
+    view_ubo: Buffer,
+    view_ubo_stride: usize,
+    view_capacity: usize,
+    view_bind_group_layout: BindGroupLayout,
+    view_bind_group: BindGroup,
+    texture_bind_group_layout: BindGroupLayout,
+    triangle_list_pipeline: RenderPipeline,
+    triangle_strip_pipeline: RenderPipeline,
+    sdf_pipeline: RenderPipeline,
+    low_res: Option<LowRes>,
 }
 
 impl GraphicsDevice {
     const DEFAULT_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+    const MAX_INSTANCES: usize = 100_000;
+    const MAX_VERTICES: usize = Self::MAX_INSTANCES * 4; // Assume rectangles.
+    const MAX_INDICES: usize = Self::MAX_INSTANCES * 6; // Assume rectangles.
+    const INITIAL_VIEWS: usize = 20;
 
     pub fn new(window: &Window) -> Result<Self, SglError> {
         let instance = Instance::new(Backends::all());
@@ -36,7 +83,8 @@ impl GraphicsDevice {
         }))
         .ok_or(SglError::General("could not get adapter".to_string()))?;
 
-        let features = Features::default();
+        // Timestamp queries are optional, they are only used for render stats.
+        let features = adapter.features() & Features::TIMESTAMP_QUERY;
 
         let limits = Limits {
             ..Default::default()
@@ -66,6 +114,159 @@ impl GraphicsDevice {
 
         let staging_belt = StagingBelt::new(1024);
 
+        let timer = features
+            .contains(Features::TIMESTAMP_QUERY)
+            .then(|| GpuTimer::new(&device, &queue));
+
+        let vbo = device.create_buffer(&BufferDescriptor {
+            label: Some("sgl::vbo"),
+            size: (size_of::<Vertex>() * Self::MAX_VERTICES) as BufferAddress,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let sdf_vbo = device.create_buffer(&BufferDescriptor {
+            label: Some("sgl::vbo::sdf"),
+            size: (size_of::<SdfVertex>() * Self::MAX_VERTICES) as BufferAddress,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let ibo = device.create_buffer(&BufferDescriptor {
+            label: Some("sgl::ibo"),
+            size: (size_of::<u32>() * Self::MAX_INDICES) as BufferAddress,
+            usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let view_ubo_stride = usize::max(
+            size_of::<[f32; 16]>(),
+            limits.min_uniform_buffer_offset_alignment as usize,
+        );
+
+        let view_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("sgl::bind_group_layout::view"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: BufferSize::new(view_ubo_stride as u64),
+                },
+                count: None,
+            }],
+        });
+
+        let (view_ubo, view_bind_group) = Self::create_view_ubo(
+            &device,
+            &view_bind_group_layout,
+            view_ubo_stride,
+            Self::INITIAL_VIEWS,
+        );
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("sgl::bind_group_layout::texture"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: true },
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("sgl::shader_module"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("sgl::pipeline_layout"),
+            bind_group_layouts: &[&view_bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let triangle_list_pipeline = Self::create_pipeline(
+            &device,
+            &pipeline_layout,
+            &shader_module,
+            surface_config.format,
+            Topology::TriangleList,
+        );
+
+        let triangle_strip_pipeline = Self::create_pipeline(
+            &device,
+            &pipeline_layout,
+            &shader_module,
+            surface_config.format,
+            Topology::TriangleStrip,
+        );
+
+        let sdf_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("sgl::shader_module::sdf"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(SDF_SHADER)),
+        });
+
+        // Shapes are cut out in the shader, so there is no texture to bind.
+        let sdf_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("sgl::pipeline_layout::sdf"),
+            bind_group_layouts: &[&view_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let sdf_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("sgl::pipeline::sdf"),
+            layout: Some(&sdf_pipeline_layout),
+            vertex: VertexState {
+                module: &sdf_shader_module,
+                entry_point: "vs_main",
+                buffers: &[SdfVertex::desc()],
+            },
+            fragment: Some(FragmentState {
+                module: &sdf_shader_module,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format: surface_config.format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                polygon_mode: PolygonMode::Fill,
+                front_face: FrontFace::Ccw,
+                strip_index_format: None,
+                cull_mode: None,
+                conservative: false,
+                unclipped_depth: false,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let low_res = window.low_res.map(|upscale| {
+            let size = PhysicalSize::new(window.size.x as u32, window.size.y as u32);
+            LowRes::new(&device, surface_config.format, upscale, size)
+        });
+
         Ok(Self {
             _instance: instance,
             surface,
@@ -76,6 +277,124 @@ impl GraphicsDevice {
             limits,
             surface_config,
             staging_belt,
+            timer,
+            vbo,
+            sdf_vbo,
+            ibo,
+            view_ubo,
+            view_ubo_stride,
+            view_capacity: Self::INITIAL_VIEWS,
+            view_bind_group_layout,
+            view_bind_group,
+            texture_bind_group_layout,
+            triangle_list_pipeline,
+            triangle_strip_pipeline,
+            sdf_pipeline,
+            low_res,
+        })
+    }
+
+    /// A uniform buffer for `capacity` view transforms, `stride` bytes apart, and the bind group
+    /// selecting one of them by dynamic offset.
+    fn create_view_ubo(
+        device: &Device,
+        layout: &BindGroupLayout,
+        stride: usize,
+        capacity: usize,
+    ) -> (Buffer, BindGroup) {
+        let view_ubo = device.create_buffer(&BufferDescriptor {
+            label: Some("sgl::ubo::view"),
+            size: stride as u64 * capacity as u64,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let view_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("sgl::bind_group::view"),
+            layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::Buffer(BufferBinding {
+                    buffer: &view_ubo,
+                    offset: 0,
+                    size: BufferSize::new(stride as u64),
+                }),
+            }],
+        });
+
+        (view_ubo, view_bind_group)
+    }
+
+    /// Make room for `count` views, growing the view buffer when a frame needs more than it
+    /// holds.
+    fn reserve_views(&mut self, count: usize) {
+        if count <= self.view_capacity {
+            return;
+        }
+
+        self.view_capacity = view_capacity(self.view_capacity, count);
+        (self.view_ubo, self.view_bind_group) = Self::create_view_ubo(
+            &self.device,
+            &self.view_bind_group_layout,
+            self.view_ubo_stride,
+            self.view_capacity,
+        );
+    }
+
+    fn create_pipeline(
+        device: &Device,
+        layout: &PipelineLayout,
+        shader_module: &ShaderModule,
+        format: TextureFormat,
+        topology: Topology,
+    ) -> RenderPipeline {
+        let (label, topology, strip_index_format) = match topology {
+            Topology::TriangleList => (
+                "sgl::pipeline::triangle_list",
+                PrimitiveTopology::TriangleList,
+                None,
+            ),
+            Topology::TriangleStrip => (
+                "sgl::pipeline::triangle_strip",
+                PrimitiveTopology::TriangleStrip,
+                Some(IndexFormat::Uint32),
+            ),
+        };
+
+        device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
+            vertex: VertexState {
+                module: shader_module,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+            },
+            fragment: Some(FragmentState {
+                module: shader_module,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState {
+                topology,
+                polygon_mode: PolygonMode::Fill,
+                front_face: FrontFace::Ccw,
+                strip_index_format,
+                // Transforms may mirror geometry, which flips its winding.
+                cull_mode: None,
+                conservative: false,
+                unclipped_depth: false,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
         })
     }
 
@@ -105,4 +424,969 @@ impl GraphicsDevice {
         frame.present();
         self.staging_belt.recall();
     }
+
+    fn pipeline(&self, topology: Topology) -> &RenderPipeline {
+        match topology {
+            Topology::TriangleList => &self.triangle_list_pipeline,
+            Topology::TriangleStrip => &self.triangle_strip_pipeline,
+        }
+    }
+
+    fn render(
+        &mut self,
+        render_commands: &RenderCommands,
+        target_view: &TextureView,
+        target_size: PhysicalSize<u32>,
+        encoder: &mut CommandEncoder,
+    ) -> RenderStats {
+        let mut stats = render_commands.stats();
+
+        if !render_commands.indices.is_empty() {
+            let vertices_size = size_of::<Vertex>() as u64 * render_commands.vertices.len() as u64;
+            let indices_size = size_of::<u32>() as u64 * render_commands.indices.len() as u64;
+
+            if let Some(vertices_size) = BufferSize::new(vertices_size) {
+                self.staging_belt
+                    .write_buffer(encoder, &self.vbo, 0, vertices_size, &self.device)
+                    .copy_from_slice(cast_slice(&render_commands.vertices));
+            }
+
+            self.staging_belt
+                .write_buffer(
+                    encoder,
+                    &self.ibo,
+                    0,
+                    BufferSize::new(indices_size).expect("size must be non-zero"),
+                    &self.device,
+                )
+                .copy_from_slice(cast_slice(&render_commands.indices));
+
+            stats.bytes_uploaded += vertices_size + indices_size;
+        }
+
+        if !render_commands.sdf_vertices.is_empty() {
+            let sdf_vertices_size =
+                size_of::<SdfVertex>() as u64 * render_commands.sdf_vertices.len() as u64;
+
+            self.staging_belt
+                .write_buffer(
+                    encoder,
+                    &self.sdf_vbo,
+                    0,
+                    BufferSize::new(sdf_vertices_size).expect("size must be non-zero"),
+                    &self.device,
+                )
+                .copy_from_slice(cast_slice(&render_commands.sdf_vertices));
+
+            stats.bytes_uploaded += sdf_vertices_size;
+        }
+
+        let view_count = render_commands
+            .commands
+            .iter()
+            .filter(|command| matches!(command, RenderCommand::View(_)))
+            .count();
+        self.reserve_views(view_count);
+
+        let mut view_ubo_offset = 0;
+
+        for render_command in render_commands.commands.iter() {
+            if let RenderCommand::View(view) = render_command {
+                let transform_size = size_of::<[f32; 16]>() as u64;
+
+                self.staging_belt
+                    .write_buffer(
+                        encoder,
+                        &self.view_ubo,
+                        view_ubo_offset,
+                        BufferSize::new(transform_size).expect("size must be non-zero"),
+                        &self.device,
+                    )
+                    .copy_from_slice(cast_slice(&[view.transform()]));
+
+                view_ubo_offset += self.view_ubo_stride as BufferAddress;
+                stats.bytes_uploaded += transform_size;
+            }
+        }
+
+        let color_attachment = RenderPassColorAttachment {
+            view: target_view,
+            ops: Operations {
+                load: render_commands
+                    .clear_color
+                    .map_or(LoadOp::Load, |color| LoadOp::Clear(Color::from(color))),
+                store: true,
+            },
+            resolve_target: None,
+        };
+
+        {
+            let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("sgl::render_pass"),
+                color_attachments: &[Some(color_attachment)],
+                depth_stencil_attachment: None,
+            });
+
+            rpass.set_index_buffer(self.ibo.slice(..), IndexFormat::Uint32);
+
+            // Only issue state changes when they differ from what is already bound. The
+            // topology is `None` while the signed distance pipeline is bound.
+            let mut current_topology = None;
+            let mut current_texture: Option<&Texture> = None;
+            let mut view_offset: DynamicOffset = 0;
+
+            for render_command in render_commands.commands.iter() {
+                match render_command {
+                    RenderCommand::Draw {
+                        topology,
+                        texture,
+                        vertices,
+                        indices,
+                    } => {
+                        let Some(gpu_texture) = texture.raw::<GpuTexture>() else {
+                            log::warn!("skipping draw with a texture from another backend");
+                            continue;
+                        };
+
+                        if current_topology != Some(Some(*topology)) {
+                            if !matches!(current_topology, Some(Some(_))) {
+                                rpass.set_vertex_buffer(0, self.vbo.slice(..));
+                            }
+
+                            rpass.set_pipeline(self.pipeline(*topology));
+                            current_topology = Some(Some(*topology));
+                        }
+
+                        if !current_texture.is_some_and(|t| std::ptr::eq(t, *texture)) {
+                            rpass.set_bind_group(1, &gpu_texture.bind_group, &[]);
+                            current_texture = Some(texture);
+                        }
+
+                        rpass.draw_indexed(indices.clone(), vertices.start as i32, 0..1);
+                    }
+
+                    RenderCommand::DrawSdf { vertices, indices } => {
+                        if current_topology != Some(None) {
+                            rpass.set_vertex_buffer(0, self.sdf_vbo.slice(..));
+                            rpass.set_pipeline(&self.sdf_pipeline);
+                            current_topology = Some(None);
+                        }
+
+                        rpass.draw_indexed(indices.clone(), vertices.start as i32, 0..1);
+                    }
+
+                    RenderCommand::View(view) => {
+                        let (x, y, width, height) =
+                            view.viewport_pixels(target_size.width, target_size.height);
+                        rpass.set_viewport(
+                            x as f32,
+                            y as f32,
+                            width as f32,
+                            height as f32,
+                            0.0,
+                            1.0,
+                        );
+                        rpass.set_scissor_rect(x, y, width, height);
+
+                        rpass.set_bind_group(0, &self.view_bind_group, &[view_offset]);
+                        view_offset += self.view_ubo_stride as DynamicOffset;
+                    }
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+impl Backend for GraphicsDevice {
+    fn create_texture(
+        &self,
+        width: u32,
+        height: u32,
+        label: Option<&str>,
+    ) -> Box<dyn Any + Send + Sync> {
+        Box::new(GpuTexture::new(self, width, height, label))
+    }
+
+    fn upload_texture(&self, texture: &Texture, bitmap: &Bitmap) -> Result<(), SglError> {
+        let gpu_texture = texture.raw::<GpuTexture>().ok_or(SglError::General(
+            "texture was not created by this backend".to_string(),
+        ))?;
+
+        self.queue.write_texture(
+            ImageCopyTexture {
+                aspect: TextureAspect::All,
+                texture: &gpu_texture.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+            },
+            bitmap,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(4 * gpu_texture.size.width),
+                rows_per_image: std::num::NonZeroU32::new(gpu_texture.size.height),
+            },
+            gpu_texture.size,
+        );
+
+        Ok(())
+    }
+
+    fn upload_texture_region(
+        &self,
+        texture: &Texture,
+        x: u32,
+        y: u32,
+        bitmap: &Bitmap,
+        source: (u32, u32, u32, u32),
+    ) -> Result<(), SglError> {
+        let gpu_texture = texture.raw::<GpuTexture>().ok_or(SglError::General(
+            "texture was not created by this backend".to_string(),
+        ))?;
+
+        // Queue writes take rows of any length, only buffer copies need them 256 byte aligned.
+        let (source_x, source_y, width, height) = source;
+        let offset = 4 * (source_y as usize * bitmap.width() as usize + source_x as usize);
+
+        self.queue.write_texture(
+            ImageCopyTexture {
+                aspect: TextureAspect::All,
+                texture: &gpu_texture.texture,
+                mip_level: 0,
+                origin: Origin3d { x, y, z: 0 },
+            },
+            &bitmap[offset..],
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(4 * bitmap.width()),
+                rows_per_image: std::num::NonZeroU32::new(height),
+            },
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn download_texture(&self, texture: &Texture) -> Result<Bitmap, SglError> {
+        let gpu_texture = texture.raw::<GpuTexture>().ok_or(SglError::General(
+            "texture was not created by this backend".to_string(),
+        ))?;
+
+        // Rows copied into a buffer have to start 256 byte aligned, the padding is dropped again
+        // when reading them out.
+        let size = gpu_texture.size;
+        let bytes_per_row = 4 * size.width as usize;
+        let padded_bytes_per_row = padded_bytes_per_row(size.width);
+
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("sgl::buffer::texture_readback"),
+            size: padded_bytes_per_row as u64 * size.height as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.create_command_encoder();
+        encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                aspect: TextureAspect::All,
+                texture: &gpu_texture.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+            },
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row as u32),
+                    rows_per_image: std::num::NonZeroU32::new(size.height),
+                },
+            },
+            size,
+        );
+        self.queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(Maintain::Wait);
+
+        receiver
+            .recv()
+            .map_err(|e| SglError::General(e.to_string()))?
+            .map_err(|e| SglError::General(e.to_string()))?;
+
+        let pixels = {
+            let data = slice.get_mapped_range();
+            data.chunks(padded_bytes_per_row)
+                .flat_map(|row| row[..bytes_per_row].chunks(4))
+                .map(Pixel::try_from)
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        buffer.unmap();
+
+        Bitmap::from_pixels(size.width, size.height, pixels)
+    }
+
+    fn submit(&mut self, render_commands: RenderCommands) -> RenderStats {
+        if let Some(timer) = self.timer.as_mut() {
+            timer.collect(&self.device);
+        }
+
+        let (frame, surface_view) = match self.get_frame() {
+            Ok((frame, surface_view)) => (frame, surface_view),
+            Err(SurfaceError::Lost | SurfaceError::Outdated) => {
+                let physical_size =
+                    PhysicalSize::new(self.surface_config.width, self.surface_config.height);
+                self.resize(physical_size);
+                return RenderStats::default();
+            }
+            Err(SurfaceError::OutOfMemory) => {
+                log::error!("surface out of memory");
+                return RenderStats::default();
+            }
+            Err(SurfaceError::Timeout) => {
+                log::warn!("surface timeout");
+                return RenderStats::default();
+            }
+        };
+
+        let mut encoder = self.create_command_encoder();
+
+        let timed = match self.timer.as_ref() {
+            Some(timer) if timer.ready() => {
+                timer.begin(&mut encoder);
+                true
+            }
+            _ => false,
+        };
+
+        let surface_size = PhysicalSize::new(self.surface_config.width, self.surface_config.height);
+
+        // Low resolution windows are drawn offscreen first, then scaled up to the surface.
+        let mut stats = match self.low_res.take() {
+            Some(low_res) => {
+                let stats = self.render(
+                    &render_commands,
+                    &low_res.texture_view,
+                    low_res.size,
+                    &mut encoder,
+                );
+                low_res.draw(&self.queue, &surface_view, surface_size, &mut encoder);
+                self.low_res = Some(low_res);
+                stats
+            }
+            None => self.render(&render_commands, &surface_view, surface_size, &mut encoder),
+        };
+
+        if let Some(timer) = self.timer.as_ref() {
+            if timed {
+                timer.end(&mut encoder);
+            }
+            stats.gpu_time = timer.last();
+        }
+
+        self.present(frame, encoder);
+
+        if let Some(timer) = self.timer.as_mut().filter(|_| timed) {
+            timer.map();
+        }
+
+        stats
+    }
+}
+
+/// Room for at least `needed` views, doubling `capacity` so growth happens rarely.
+fn view_capacity(capacity: usize, needed: usize) -> usize {
+    let mut capacity = capacity.max(1);
+    while capacity < needed {
+        capacity *= 2;
+    }
+    capacity
+}
+
+/// Bytes in a row of `width` pixels, padded to the alignment buffer copies need.
+fn padded_bytes_per_row(width: u32) -> usize {
+    let alignment = COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+    (4 * width as usize).div_ceil(alignment) * alignment
+}
+
+/// The wgpu resources backing a [`Texture`].
+#[derive(Debug)]
+pub(crate) struct GpuTexture {
+    pub(crate) size: Extent3d,
+    pub(crate) sampler: Sampler,
+    pub(crate) texture: wgpu::Texture,
+    pub(crate) texture_view: TextureView,
+    pub(crate) bind_group: BindGroup,
+}
+
+impl GpuTexture {
+    fn new(gpu: &GraphicsDevice, width: u32, height: u32, label: Option<&str>) -> Self {
+        let sampler = gpu.device.create_sampler(&SamplerDescriptor {
+            label,
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Nearest, // TODO: Make sampler configuration configurable.
+            min_filter: FilterMode::Nearest,
+            mipmap_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = gpu.device.create_texture(&TextureDescriptor {
+            label,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: gpu.surface_config.format,
+            usage: TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC,
+        });
+
+        let texture_view = texture.create_view(&TextureViewDescriptor {
+            label,
+            ..Default::default()
+        });
+
+        let bind_group = gpu.device.create_bind_group(&BindGroupDescriptor {
+            label,
+            layout: &gpu.texture_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::Sampler(&sampler),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&texture_view),
+                },
+            ],
+        });
+
+        Self {
+            size,
+            sampler,
+            texture,
+            texture_view,
+            bind_group,
+        }
+    }
+}
+
+/// The offscreen target of a low resolution window, and the pass scaling it up to the surface.
+struct LowRes {
+    upscale: Upscale,
+    size: PhysicalSize<u32>,
+    texture_view: TextureView,
+    ubo: Buffer,
+    bind_group: BindGroup,
+    pipeline: RenderPipeline,
+}
+
+impl LowRes {
+    const UBO_SIZE: u64 = size_of::<[f32; 4]>() as u64;
+
+    fn new(
+        device: &Device,
+        format: TextureFormat,
+        upscale: Upscale,
+        size: PhysicalSize<u32>,
+    ) -> Self {
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("sgl::texture::low_res"),
+            size: Extent3d {
+                width: size.width.max(1),
+                height: size.height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+        });
+
+        let texture_view = texture.create_view(&TextureViewDescriptor::default());
+
+        // Nearest neighbor is done by sampling texel centers, so one linear sampler serves both.
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("sgl::sampler::low_res"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let ubo = device.create_buffer(&BufferDescriptor {
+            label: Some("sgl::ubo::low_res"),
+            size: Self::UBO_SIZE,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("sgl::bind_group_layout::low_res"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(Self::UBO_SIZE),
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("sgl::bind_group::low_res"),
+            layout: &bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: ubo.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(&texture_view),
+                },
+            ],
+        });
+
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("sgl::shader_module::upscale"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(UPSCALE_SHADER)),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("sgl::pipeline_layout::upscale"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("sgl::pipeline::upscale"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader_module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(FragmentState {
+                module: &shader_module,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            multiview: None,
+        });
+
+        Self {
+            upscale,
+            size,
+            texture_view,
+            ubo,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    /// Scale the offscreen target up into `target_view`, with black bars around it.
+    fn draw(
+        &self,
+        queue: &Queue,
+        target_view: &TextureView,
+        target_size: PhysicalSize<u32>,
+        encoder: &mut CommandEncoder,
+    ) {
+        let (x, y, width, height) = self.upscale.rect(self.size, target_size);
+
+        // Sharp bilinear blends over one screen pixel at the edges of each pixel, nearest
+        // neighbor over none.
+        let sharpness = match self.upscale {
+            Upscale::Integer | Upscale::Letterbox => 0.0,
+            Upscale::SharpBilinear => (width as f32 / self.size.width.max(1) as f32)
+                .floor()
+                .max(1.0),
+        };
+
+        queue.write_buffer(
+            &self.ubo,
+            0,
+            cast_slice(&[
+                self.size.width as f32,
+                self.size.height as f32,
+                sharpness,
+                0.0,
+            ]),
+        );
+
+        let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("sgl::render_pass::upscale"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target_view,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::BLACK),
+                    store: true,
+                },
+                resolve_target: None,
+            })],
+            depth_stencil_attachment: None,
+        });
+
+        rpass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &self.bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+pub(crate) struct GpuTimer {
+    query_set: QuerySet,
+    readback_buffer: Buffer,
+    period: f32,
+    pending: bool,
+    map_state: Arc<AtomicU8>,
+    last: Option<Duration>,
+}
+
+impl GpuTimer {
+    const QUERY_COUNT: u32 = 2;
+    const MAP_WAITING: u8 = 0;
+    const MAP_OK: u8 = 1;
+    const MAP_FAILED: u8 = 2;
+    const BUFFER_SIZE: u64 = size_of::<u64>() as u64 * Self::QUERY_COUNT as u64;
+
+    fn new(device: &Device, queue: &Queue) -> Self {
+        let query_set = device.create_query_set(&QuerySetDescriptor {
+            label: Some("sgl::query_set::timer"),
+            ty: QueryType::Timestamp,
+            count: Self::QUERY_COUNT,
+        });
+
+        let readback_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("sgl::buffer::timer_readback"),
+            size: Self::BUFFER_SIZE,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            query_set,
+            readback_buffer,
+            period: queue.get_timestamp_period(),
+            pending: false,
+            map_state: Arc::new(AtomicU8::new(Self::MAP_WAITING)),
+            last: None,
+        }
+    }
+
+    /// The most recently completed measurement.
+    pub(crate) fn last(&self) -> Option<Duration> {
+        self.last
+    }
+
+    /// Whether timestamps can be written this frame; the readback buffer can't be a copy
+    /// destination while a previous measurement is still waiting to be mapped.
+    pub(crate) fn ready(&self) -> bool {
+        !self.pending
+    }
+
+    pub(crate) fn begin(&self, encoder: &mut CommandEncoder) {
+        encoder.write_timestamp(&self.query_set, 0);
+    }
+
+    pub(crate) fn end(&self, encoder: &mut CommandEncoder) {
+        encoder.write_timestamp(&self.query_set, 1);
+        encoder.resolve_query_set(
+            &self.query_set,
+            0..Self::QUERY_COUNT,
+            &self.readback_buffer,
+            0,
+        );
+    }
+
+    /// Request the readback of timestamps written since the last submission.
+    pub(crate) fn map(&mut self) {
+        let map_state = self.map_state.clone();
+        self.readback_buffer
+            .slice(..)
+            .map_async(MapMode::Read, move |result| {
+                let state = if result.is_ok() {
+                    Self::MAP_OK
+                } else {
+                    Self::MAP_FAILED
+                };
+                map_state.store(state, Ordering::Release);
+            });
+        self.pending = true;
+    }
+
+    /// Pick up a previously requested readback if the GPU has finished with it.
+    pub(crate) fn collect(&mut self, device: &Device) {
+        if !self.pending {
+            return;
+        }
+
+        device.poll(Maintain::Poll);
+
+        match self.map_state.swap(Self::MAP_WAITING, Ordering::Acquire) {
+            Self::MAP_OK => (),
+            Self::MAP_FAILED => {
+                self.pending = false;
+                return;
+            }
+            _ => return,
+        }
+
+        {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            let timestamps: &[u64] = cast_slice(&data);
+            let ticks = timestamps[1].saturating_sub(timestamps[0]);
+            self.last = Some(Duration::from_nanos(
+                (ticks as f64 * self.period as f64) as u64,
+            ));
+        }
+
+        self.readback_buffer.unmap();
+        self.pending = false;
+    }
+}
+
+static SHADER: &str = r"
+// Vertex
+
+@group(0) @binding(0)
+var<uniform> scene_transform: mat4x4<f32>;
+
+struct VsIn {
+    @location(0) coords: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) fill_color: vec4<f32>,
+};
+
+struct VsOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) fill_color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: VsIn) -> VsOut {
+    let position = scene_transform * vec4<f32>(in.coords, 0.0, 1.0);
+
+    return VsOut(position, in.tex_coords, in.fill_color);
+}
+
+struct FsIn {
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) fill_color: vec4<f32>,
+};
+
+struct FsOut {
+    @location(0) color: vec4<f32>,
+};
+
+@group(1) @binding(0)
+var texture_sampler: sampler;
+@group(1) @binding(1)
+var texture: texture_2d<f32>;
+
+@fragment
+fn fs_main(in: FsIn) -> FsOut {
+    let color = textureSample(texture, texture_sampler, in.tex_coords) * in.fill_color;
+
+    return FsOut(color);
+}
+";
+
+static SDF_SHADER: &str = r"
+// Vertex
+
+@group(0) @binding(0)
+var<uniform> scene_transform: mat4x4<f32>;
+
+struct VsIn {
+    @location(0) coords: vec2<f32>,
+    @location(1) local: vec2<f32>,
+    @location(2) half_size: vec2<f32>,
+    @location(3) radii: vec4<f32>,
+    @location(4) border: f32,
+    @location(5) fill_color: vec4<f32>,
+    @location(6) border_color: vec4<f32>,
+};
+
+struct VsOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+    @location(1) half_size: vec2<f32>,
+    @location(2) radii: vec4<f32>,
+    @location(3) border: f32,
+    @location(4) fill_color: vec4<f32>,
+    @location(5) border_color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: VsIn) -> VsOut {
+    let position = scene_transform * vec4<f32>(in.coords, 0.0, 1.0);
+
+    return VsOut(
+        position,
+        in.local,
+        in.half_size,
+        in.radii,
+        in.border,
+        in.fill_color,
+        in.border_color,
+    );
+}
+
+// Fragment
+
+// Distance from p to the edge of a box centered on the origin, negative inside. The radii are
+// for the top-left, top-right, bottom-right and bottom-left corners.
+fn rounded_box(p: vec2<f32>, half_size: vec2<f32>, radii: vec4<f32>) -> f32 {
+    var radius = select(radii.w, radii.z, p.x > 0.0);
+    if (p.y < 0.0) {
+        radius = select(radii.x, radii.y, p.x > 0.0);
+    }
+
+    let q = abs(p) - half_size + vec2<f32>(radius);
+
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - radius;
+}
+
+// How much of a pixel is inside an edge at this distance.
+fn coverage(distance: f32, pixel: f32) -> f32 {
+    return clamp(0.5 - distance / pixel, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
+    let distance = rounded_box(in.local, in.half_size, in.radii);
+    let pixel = max(fwidth(distance), 0.0001);
+
+    let inside = coverage(distance, pixel);
+    let fill = inside * in.fill_color.a;
+    let border = max(coverage(distance - in.border, pixel) - inside, 0.0) * in.border_color.a;
+    let alpha = fill + border;
+
+    if (alpha <= 0.0) {
+        discard;
+    }
+
+    let color = (in.fill_color.rgb * fill + in.border_color.rgb * border) / alpha;
+
+    return vec4<f32>(color, alpha);
+}
+";
+
+static UPSCALE_SHADER: &str = r"
+struct Upscale {
+    source_size: vec2<f32>,
+    sharpness: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> upscale: Upscale;
+@group(0) @binding(1)
+var source_sampler: sampler;
+@group(0) @binding(2)
+var source: texture_2d<f32>;
+
+struct VsOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+// One triangle covering the whole viewport.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VsOut {
+    let tex_coords = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    let position = vec4<f32>(tex_coords.x * 2.0 - 1.0, 1.0 - tex_coords.y * 2.0, 0.0, 1.0);
+
+    return VsOut(position, tex_coords);
+}
+
+@fragment
+fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
+    let texel = in.tex_coords * upscale.source_size;
+    var coords = floor(texel) + 0.5;
+
+    // Only blend towards the neighboring pixel within the last screen pixel before an edge.
+    if (upscale.sharpness > 0.0) {
+        let offset = fract(texel) - 0.5;
+        let band = vec2<f32>(0.5 - 0.5 / upscale.sharpness);
+        coords = coords + (offset - clamp(offset, -band, band)) * upscale.sharpness;
+    }
+
+    return textureSample(source, source_sampler, coords / upscale.source_size);
+}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_capacity_doubles_until_the_frame_fits() {
+        assert_eq!(20, view_capacity(20, 3));
+        assert_eq!(40, view_capacity(20, 21));
+        assert_eq!(160, view_capacity(20, 150));
+    }
+
+    #[test]
+    fn rows_are_padded_to_the_copy_alignment() {
+        assert_eq!(256, padded_bytes_per_row(1));
+        assert_eq!(256, padded_bytes_per_row(64));
+        assert_eq!(512, padded_bytes_per_row(65));
+        assert_eq!(1280, padded_bytes_per_row(320));
+    }
 }
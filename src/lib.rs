@@ -1,27 +1,59 @@
 #![allow(dead_code)]
 
+pub use crate::backend::{Backend, RenderCommand, RenderCommands, Topology};
 pub use crate::bitmap::Bitmap;
+pub use crate::bitmap_font::BitmapFont;
+pub use crate::camera::{Camera, Follow};
+pub use crate::canvas::Canvas;
 pub use crate::error::SglError;
 pub use crate::fs::*;
+pub use crate::geometry::Vertex;
 pub use crate::graphics_device::GraphicsDevice;
 pub use crate::key::Key;
+pub use crate::paint::Paint;
+pub use crate::path::{FillRule, Path};
 pub use crate::pixel::Pixel;
+pub use crate::recording_backend::{RecordedCommand, RecordedFrame, RecordingBackend};
 pub use crate::renderer::Renderer;
 pub use crate::scene::Scene;
+pub use crate::sdf::SdfVertex;
+pub use crate::shape::PointStyle;
+pub use crate::sprite::Sprite;
+pub use crate::stats::RenderStats;
+pub use crate::stroke::{LineCap, LineJoin, Stroke};
+pub use crate::text::{Font, TextAlign, TextStyle, VerticalAlign};
+pub use crate::text_layout::{TextFont, TextLayout};
 pub use crate::texture::Texture;
-pub use crate::view::View;
+pub use crate::upscale::Upscale;
+pub use crate::view::{Origin, View};
 pub use crate::window::Window;
 
+mod backend;
 mod bitmap;
+mod bitmap_font;
+mod camera;
+mod canvas;
+mod debug_text;
 mod error;
 mod fs;
 mod geometry;
 mod graphics_device;
 mod key;
+mod paint;
+mod path;
 mod pixel;
+mod recording_backend;
 mod renderer;
 mod scene;
+mod sdf;
 pub(crate) mod shape;
+mod sprite;
+mod stats;
+mod stroke;
+mod text;
+mod text_layout;
 mod texture;
+mod triangulate;
+mod upscale;
 mod view;
 mod window;
@@ -0,0 +1,293 @@
+use mint::Vector2;
+use sgl_math::{v2, Transform, Vec2};
+
+use crate::{geometry::Vertex, Pixel};
+
+/// What filled shapes are colored with. Gradient positions are in the same coordinates as the
+/// shape they fill.
+///
+/// A [`Pixel`] converts into a solid paint, so it can be passed anywhere a paint is expected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    Solid(Pixel),
+    /// Colors change along the line from `start` to `end`, and stay the same across it.
+    LinearGradient {
+        start: Vec2,
+        end: Vec2,
+        stops: Vec<(f32, Pixel)>,
+    },
+    /// Colors change with the distance from `center`, reaching the last stop at `radius`.
+    RadialGradient {
+        center: Vec2,
+        radius: f32,
+        stops: Vec<(f32, Pixel)>,
+    },
+}
+
+impl Paint {
+    /// Largest difference in any color channel between the gradient and the colors
+    /// interpolated between vertices, before triangles are split.
+    const TOLERANCE: f32 = 1.0 / 255.0;
+    const MAX_DEPTH: u32 = 6;
+
+    pub fn linear_gradient<V>(start: V, end: V, start_color: Pixel, end_color: Pixel) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        Self::LinearGradient {
+            start: start.into().into(),
+            end: end.into().into(),
+            stops: vec![(0.0, start_color), (1.0, end_color)],
+        }
+    }
+
+    pub fn radial_gradient<V>(
+        center: V,
+        radius: f32,
+        inner_color: Pixel,
+        outer_color: Pixel,
+    ) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        Self::RadialGradient {
+            center: center.into().into(),
+            radius,
+            stops: vec![(0.0, inner_color), (1.0, outer_color)],
+        }
+    }
+
+    /// Add a color part way along a gradient, with `offset` from 0 at the start to 1 at the end.
+    /// Solid paints are left unchanged.
+    pub fn with_stop(mut self, offset: f32, color: Pixel) -> Self {
+        if let Self::LinearGradient { stops, .. } | Self::RadialGradient { stops, .. } = &mut self {
+            let offset = offset.clamp(0.0, 1.0);
+            let i = stops.partition_point(|(o, _)| *o <= offset);
+            stops.insert(i, (offset, color));
+        }
+
+        self
+    }
+
+    pub(crate) fn color_at(&self, point: Vec2) -> [f32; 4] {
+        let (offset, stops) = match self {
+            Self::Solid(color) => return color.to_array(),
+            Self::LinearGradient { start, end, stops } => {
+                let axis = *end - *start;
+                let length = axis.dot(axis);
+                let offset = if length > 0.0 {
+                    (point - *start).dot(axis) / length
+                } else {
+                    0.0
+                };
+
+                (offset, stops)
+            }
+            Self::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => {
+                let offset = if *radius > 0.0 {
+                    (point - *center).len() / radius
+                } else {
+                    0.0
+                };
+
+                (offset, stops)
+            }
+        };
+
+        let i = stops.partition_point(|(o, _)| *o <= offset);
+
+        match (stops.get(i.wrapping_sub(1)), stops.get(i)) {
+            (Some((from, from_color)), Some((to, to_color))) if to > from => {
+                let t = (offset - from) / (to - from);
+                lerp(from_color.to_array(), to_color.to_array(), t)
+            }
+            (Some((_, color)), _) | (None, Some((_, color))) => color.to_array(),
+            (None, None) => Pixel::WHITE.to_array(),
+        }
+    }
+
+    /// Color a triangle list with the paint. `transform` is the one the geometry was built with,
+    /// and triangles are split until the colors between their vertices follow the gradient.
+    pub(crate) fn fill(
+        &self,
+        (vertices, indices): (Vec<Vertex>, Vec<u32>),
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        if let Self::Solid(color) = self {
+            let fill_color = color.to_array();
+            let vertices = vertices
+                .into_iter()
+                .map(|vertex| Vertex {
+                    fill_color,
+                    ..vertex
+                })
+                .collect();
+
+            return (vertices, indices);
+        }
+
+        let Some(inverse) = transform.inverse() else {
+            return (vertices, indices);
+        };
+
+        let color_at =
+            |coords: [f32; 2]| self.color_at(inverse.transform_point(v2(coords[0], coords[1])));
+
+        let painted: Vec<Vertex> = vertices
+            .iter()
+            .map(|vertex| Vertex {
+                fill_color: color_at(vertex.coords),
+                ..*vertex
+            })
+            .collect();
+
+        let mut output = (Vec::new(), Vec::new());
+
+        for triangle in indices.chunks_exact(3) {
+            self.subdivide(
+                [
+                    painted[triangle[0] as usize],
+                    painted[triangle[1] as usize],
+                    painted[triangle[2] as usize],
+                ],
+                &color_at,
+                0,
+                &mut output,
+            );
+        }
+
+        output
+    }
+
+    fn subdivide(
+        &self,
+        corners: [Vertex; 3],
+        color_at: &impl Fn([f32; 2]) -> [f32; 4],
+        depth: u32,
+        output: &mut (Vec<Vertex>, Vec<u32>),
+    ) {
+        let [a, b, c] = corners;
+        let ab = midpoint(a, b, color_at);
+        let bc = midpoint(b, c, color_at);
+        let ca = midpoint(c, a, color_at);
+
+        let error = [(ab, a, b), (bc, b, c), (ca, c, a)]
+            .iter()
+            .map(|(mid, from, to)| {
+                let expected = lerp(from.fill_color, to.fill_color, 0.5);
+                (0..4)
+                    .map(|i| (mid.fill_color[i] - expected[i]).abs())
+                    .fold(0.0, f32::max)
+            })
+            .fold(0.0, f32::max);
+
+        let size = [(a, b), (b, c), (c, a)]
+            .iter()
+            .map(|(from, to)| {
+                v2(to.coords[0] - from.coords[0], to.coords[1] - from.coords[1]).len()
+            })
+            .fold(0.0, f32::max);
+
+        if error <= Self::TOLERANCE || depth >= Self::MAX_DEPTH || size <= 1.0 {
+            let first = output.0.len() as u32;
+            output.0.extend([a, b, c]);
+            output.1.extend([first, first + 1, first + 2]);
+            return;
+        }
+
+        for corners in [[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]] {
+            self.subdivide(corners, color_at, depth + 1, output);
+        }
+    }
+}
+
+impl From<Pixel> for Paint {
+    fn from(color: Pixel) -> Self {
+        Self::Solid(color)
+    }
+}
+
+fn midpoint(a: Vertex, b: Vertex, color_at: &impl Fn([f32; 2]) -> [f32; 4]) -> Vertex {
+    let coords = [
+        (a.coords[0] + b.coords[0]) / 2.0,
+        (a.coords[1] + b.coords[1]) / 2.0,
+    ];
+    let tex_coords = [
+        (a.tex_coords[0] + b.tex_coords[0]) / 2.0,
+        (a.tex_coords[1] + b.tex_coords[1]) / 2.0,
+    ];
+
+    Vertex::new(coords, tex_coords, color_at(coords))
+}
+
+fn lerp(from: [f32; 4], to: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| from[i] + (to[i] - from[i]) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_gradient_interpolates_along_axis() {
+        let paint = Paint::linear_gradient([0.0, 0.0], [10.0, 0.0], Pixel::BLACK, Pixel::WHITE);
+
+        assert_eq!(Pixel::BLACK.to_array(), paint.color_at(v2(-5.0, 3.0)));
+        assert_eq!([0.5, 0.5, 0.5, 1.0], paint.color_at(v2(5.0, 100.0)));
+        assert_eq!(Pixel::WHITE.to_array(), paint.color_at(v2(20.0, 0.0)));
+    }
+
+    #[test]
+    fn gradient_stops_are_kept_in_order() {
+        let paint = Paint::radial_gradient([0.0, 0.0], 10.0, Pixel::BLACK, Pixel::WHITE)
+            .with_stop(0.5, Pixel::RED);
+
+        assert_eq!(Pixel::RED.to_array(), paint.color_at(v2(0.0, 5.0)));
+        assert_eq!([0.5, 0.0, 0.0, 1.0], paint.color_at(v2(2.5, 0.0)));
+    }
+
+    #[test]
+    fn solid_fill_only_recolors() {
+        let vertices = vec![Vertex::default(); 3];
+        let (painted, indices) =
+            Paint::Solid(Pixel::RED).fill((vertices, vec![0, 1, 2]), Transform::IDENTITY);
+
+        assert_eq!(3, painted.len());
+        assert_eq!(vec![0, 1, 2], indices);
+        assert!(painted
+            .iter()
+            .all(|v| v.fill_color == Pixel::RED.to_array()));
+    }
+
+    #[test]
+    fn linear_fill_with_two_stops_is_not_split() {
+        let paint = Paint::linear_gradient([0.0, 0.0], [100.0, 0.0], Pixel::BLACK, Pixel::WHITE);
+        let vertices = vec![
+            Vertex::new([0.0, 0.0], [0.0, 0.0], [1.0; 4]),
+            Vertex::new([100.0, 0.0], [0.0, 0.0], [1.0; 4]),
+            Vertex::new([0.0, 100.0], [0.0, 0.0], [1.0; 4]),
+        ];
+        let (painted, indices) = paint.fill((vertices, vec![0, 1, 2]), Transform::IDENTITY);
+
+        assert_eq!(3, indices.len());
+        assert_eq!(Pixel::WHITE.to_array(), painted[1].fill_color);
+    }
+
+    #[test]
+    fn radial_fill_is_split_to_follow_gradient() {
+        let paint = Paint::radial_gradient([0.0, 0.0], 100.0, Pixel::BLACK, Pixel::WHITE)
+            .with_stop(0.5, Pixel::RED);
+        let vertices = vec![
+            Vertex::new([-100.0, -100.0], [0.0, 0.0], [1.0; 4]),
+            Vertex::new([100.0, -100.0], [0.0, 0.0], [1.0; 4]),
+            Vertex::new([0.0, 100.0], [0.0, 0.0], [1.0; 4]),
+        ];
+        let (_, indices) = paint.fill((vertices, vec![0, 1, 2]), Transform::IDENTITY);
+
+        assert!(indices.len() > 3);
+    }
+}
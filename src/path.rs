@@ -0,0 +1,359 @@
+use mint::Vector2;
+use sgl_math::{v2, Transform, Vec2};
+
+/// Decides which parts of a self-overlapping path are inside when it is filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside wherever the outlines wind around a point a different number of times one way
+    /// than the other.
+    NonZero,
+    /// Inside wherever a point is surrounded by an odd number of outlines.
+    EvenOdd,
+}
+
+/// A vector shape made of lines and curves, drawn with [`Scene::fill_path`](crate::Scene::fill_path)
+/// and [`Scene::stroke_path`](crate::Scene::stroke_path).
+///
+/// A path holds any number of sub-paths. Each starts with [`Path::move_to`] and ends at the next
+/// one or with [`Path::close`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+    tolerance: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PathSegment {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadTo(Vec2, Vec2),
+    CubicTo(Vec2, Vec2, Vec2),
+    Arc {
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+    },
+    Close,
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Path {
+    const MAX_SEGMENTS: usize = 1024;
+
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            tolerance: 0.25,
+        }
+    }
+
+    /// The largest distance, in physical pixels, that flattened curves may be from the real
+    /// ones. Defaults to a quarter of a pixel.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Start a new sub-path at `point`.
+    pub fn move_to<V>(mut self, point: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.segments.push(PathSegment::MoveTo(point.into().into()));
+        self
+    }
+
+    pub fn line_to<V>(mut self, point: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.segments.push(PathSegment::LineTo(point.into().into()));
+        self
+    }
+
+    /// A quadratic Bézier curve to `point`, pulled towards `control`.
+    pub fn quad_to<V>(mut self, control: V, point: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.segments.push(PathSegment::QuadTo(
+            control.into().into(),
+            point.into().into(),
+        ));
+        self
+    }
+
+    /// A cubic Bézier curve to `point`, leaving towards `control_1` and arriving from
+    /// `control_2`.
+    pub fn cubic_to<V>(mut self, control_1: V, control_2: V, point: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.segments.push(PathSegment::CubicTo(
+            control_1.into().into(),
+            control_2.into().into(),
+            point.into().into(),
+        ));
+        self
+    }
+
+    /// Round off the corner at `corner` with a circle of `radius`. Draws a line towards
+    /// `corner`, then an arc that ends heading towards `to`, like `arcTo` on an HTML canvas.
+    pub fn arc_to<V>(self, corner: V, to: V, radius: f32) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        let corner: Vec2 = corner.into().into();
+        let to: Vec2 = to.into().into();
+
+        let Some(current) = self.current_point() else {
+            return self.move_to(corner);
+        };
+
+        let d0 = current - corner;
+        let d1 = to - corner;
+        let cross = d0.x * d1.y - d0.y * d1.x;
+
+        if radius <= 0.0 || d0.len() == 0.0 || d1.len() == 0.0 || cross == 0.0 {
+            return self.line_to(corner);
+        }
+
+        let (d0, d1) = (d0.norm(), d1.norm());
+        let half_angle = d0.dot(d1).clamp(-1.0, 1.0).acos() / 2.0;
+
+        let tangent_distance = radius / half_angle.tan();
+        let start = corner + d0 * tangent_distance;
+        let end = corner + d1 * tangent_distance;
+        let center = corner + (d0 + d1).norm() * (radius / half_angle.sin());
+
+        let from = start - center;
+        let to = end - center;
+        let sweep_angle = (from.x * to.y - from.y * to.x).atan2(from.dot(to));
+
+        let mut path = self.line_to(start);
+        path.segments.push(PathSegment::Arc {
+            center,
+            radius,
+            start_angle: from.y.atan2(from.x),
+            sweep_angle,
+        });
+        path
+    }
+
+    /// Join the current sub-path back to where it started.
+    pub fn close(mut self) -> Self {
+        self.segments.push(PathSegment::Close);
+        self
+    }
+
+    fn current_point(&self) -> Option<Vec2> {
+        let mut start = None;
+        let mut current = None;
+
+        for segment in self.segments.iter() {
+            match *segment {
+                PathSegment::MoveTo(point) => {
+                    start = Some(point);
+                    current = Some(point);
+                }
+                PathSegment::LineTo(point)
+                | PathSegment::QuadTo(_, point)
+                | PathSegment::CubicTo(_, _, point) => {
+                    start = start.or(Some(point));
+                    current = Some(point);
+                }
+                PathSegment::Arc {
+                    center,
+                    radius,
+                    start_angle,
+                    sweep_angle,
+                } => {
+                    let angle = start_angle + sweep_angle;
+                    current = Some(center + v2(angle.cos(), angle.sin()) * radius);
+                }
+                PathSegment::Close => current = start,
+            }
+        }
+
+        current
+    }
+
+    /// The sub-paths as lines, with curves split up finely enough for how large `transform`
+    /// makes them. Each comes with whether it was closed.
+    pub(crate) fn flatten(&self, transform: Transform) -> Vec<(Vec<Vec2>, bool)> {
+        let tolerance = self.tolerance / transform.max_scale().max(f32::EPSILON);
+
+        let mut subpaths = Vec::new();
+        let mut points: Vec<Vec2> = Vec::new();
+
+        let finish = |points: &mut Vec<Vec2>, closed: bool, subpaths: &mut Vec<_>| {
+            if points.len() > 1 {
+                subpaths.push((std::mem::take(points), closed));
+            } else {
+                points.clear();
+            }
+        };
+
+        for segment in self.segments.iter() {
+            match *segment {
+                PathSegment::MoveTo(point) => {
+                    finish(&mut points, false, &mut subpaths);
+                    points.push(point);
+                }
+
+                PathSegment::LineTo(point) => points.push(point),
+
+                PathSegment::QuadTo(control, point) => {
+                    let from = *points.last().unwrap_or(&control);
+                    let dd = (from - control * 2.0 + point).len();
+                    let count = Self::segment_count((dd / (4.0 * tolerance)).sqrt());
+
+                    points.extend((1..=count).map(|i| {
+                        let t = i as f32 / count as f32;
+                        let mt = 1.0 - t;
+                        from * (mt * mt) + control * (2.0 * mt * t) + point * (t * t)
+                    }));
+                }
+
+                PathSegment::CubicTo(control_1, control_2, point) => {
+                    let from = *points.last().unwrap_or(&control_1);
+                    let dd = f32::max(
+                        (from - control_1 * 2.0 + control_2).len(),
+                        (control_1 - control_2 * 2.0 + point).len(),
+                    );
+                    let count = Self::segment_count((3.0 * dd / (4.0 * tolerance)).sqrt());
+
+                    points.extend((1..=count).map(|i| {
+                        let t = i as f32 / count as f32;
+                        let mt = 1.0 - t;
+                        from * (mt * mt * mt)
+                            + control_1 * (3.0 * mt * mt * t)
+                            + control_2 * (3.0 * mt * t * t)
+                            + point * (t * t * t)
+                    }));
+                }
+
+                PathSegment::Arc {
+                    center,
+                    radius,
+                    start_angle,
+                    sweep_angle,
+                } => {
+                    let count = if radius > tolerance {
+                        let step = 2.0 * (1.0 - tolerance / radius).acos();
+                        Self::segment_count(sweep_angle.abs() / step)
+                    } else {
+                        1
+                    };
+
+                    points.extend((0..=count).map(|i| {
+                        let angle = start_angle + sweep_angle * i as f32 / count as f32;
+                        center + v2(angle.cos(), angle.sin()) * radius
+                    }));
+                }
+
+                PathSegment::Close => {
+                    let start = points.first().copied();
+                    finish(&mut points, true, &mut subpaths);
+
+                    // Drawing carries on from the start of the closed sub-path.
+                    points.extend(start);
+                }
+            }
+        }
+
+        finish(&mut points, false, &mut subpaths);
+
+        subpaths
+    }
+
+    fn segment_count(count: f32) -> usize {
+        (count.ceil() as usize).clamp(1, Self::MAX_SEGMENTS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_flatten_to_their_points() {
+        let path = Path::new()
+            .move_to([0.0, 0.0])
+            .line_to([10.0, 0.0])
+            .line_to([10.0, 10.0])
+            .close()
+            .move_to([20.0, 20.0])
+            .line_to([30.0, 20.0]);
+
+        assert_eq!(
+            vec![
+                (vec![v2(0.0, 0.0), v2(10.0, 0.0), v2(10.0, 10.0)], true),
+                (vec![v2(20.0, 20.0), v2(30.0, 20.0)], false),
+            ],
+            path.flatten(Transform::IDENTITY)
+        );
+    }
+
+    #[test]
+    fn curves_are_flattened_within_tolerance() {
+        let path = Path::new()
+            .move_to([0.0, 0.0])
+            .quad_to([50.0, 100.0], [100.0, 0.0]);
+        let (points, _) = &path.flatten(Transform::IDENTITY)[0];
+
+        // The curve is y = 2x - x² / 50, so every point should be on it.
+        assert!(points.len() > 2);
+        for point in points {
+            let expected = 2.0 * point.x - point.x * point.x / 50.0;
+            assert!((point.y - expected).abs() < 1e-2);
+        }
+        assert_eq!(v2(100.0, 0.0), *points.last().unwrap());
+    }
+
+    #[test]
+    fn curves_get_more_points_when_scaled_up() {
+        let path =
+            Path::new()
+                .move_to([0.0, 0.0])
+                .cubic_to([0.0, 50.0], [100.0, 50.0], [100.0, 0.0]);
+
+        let small = path.flatten(Transform::IDENTITY)[0].0.len();
+        let large = path.flatten(Transform::scale(v2(10.0, 10.0)))[0].0.len();
+
+        assert!(large > small);
+    }
+
+    #[test]
+    fn arc_to_rounds_corner() {
+        let path = Path::new()
+            .move_to([0.0, 0.0])
+            .arc_to([10.0, 0.0], [10.0, 10.0], 5.0)
+            .line_to([10.0, 10.0]);
+        let (points, _) = &path.flatten(Transform::IDENTITY)[0];
+
+        assert_eq!(v2(5.0, 0.0), points[1]);
+        for point in &points[2..points.len() - 2] {
+            assert!(((*point - v2(5.0, 5.0)).len() - 5.0).abs() < 1e-4);
+        }
+        assert!((points[points.len() - 2] - v2(10.0, 5.0)).len() < 1e-4);
+    }
+
+    #[test]
+    fn arc_to_straight_line_is_a_line() {
+        let path = Path::new()
+            .move_to([0.0, 0.0])
+            .arc_to([10.0, 0.0], [20.0, 0.0], 5.0);
+
+        assert_eq!(
+            vec![(vec![v2(0.0, 0.0), v2(10.0, 0.0)], false)],
+            path.flatten(Transform::IDENTITY)
+        );
+    }
+}
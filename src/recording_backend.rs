@@ -0,0 +1,208 @@
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{
+    backend::{RenderCommand, RenderCommands, Topology},
+    geometry::Vertex,
+    Backend, Bitmap, Pixel, RenderStats, SdfVertex, SglError, Texture, View,
+};
+
+/// A [`Backend`] that draws nothing and keeps every submitted frame for inspection.
+#[derive(Debug, Default)]
+pub struct RecordingBackend {
+    next_texture_id: AtomicU32,
+    frames: Vec<RecordedFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    pub clear_color: Option<Pixel>,
+    pub commands: Vec<RecordedCommand>,
+    pub stats: RenderStats,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedCommand {
+    Draw {
+        topology: Topology,
+        texture: Option<u32>,
+        vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+    },
+    DrawSdf {
+        vertices: Vec<SdfVertex>,
+        indices: Vec<u32>,
+    },
+    View(View),
+}
+
+/// Textures keep their pixels, so what was uploaded can be downloaded again.
+#[derive(Debug)]
+struct RecordedTexture {
+    id: u32,
+    width: u32,
+    height: u32,
+    pixels: Mutex<Vec<Pixel>>,
+}
+
+impl RecordingBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// The id draws using this texture are recorded with, if it was created by a recording backend.
+    pub fn texture_id(texture: &Texture) -> Option<u32> {
+        texture.raw::<RecordedTexture>().map(|t| t.id)
+    }
+
+    fn recorded(texture: &Texture) -> Result<&RecordedTexture, SglError> {
+        texture.raw::<RecordedTexture>().ok_or(SglError::General(
+            "texture was not created by this backend".to_string(),
+        ))
+    }
+}
+
+impl Backend for RecordingBackend {
+    fn create_texture(
+        &self,
+        width: u32,
+        height: u32,
+        _label: Option<&str>,
+    ) -> Box<dyn Any + Send + Sync> {
+        let id = self.next_texture_id.fetch_add(1, Ordering::Relaxed);
+        let pixels = vec![Pixel::rgba(0, 0, 0, 0); width as usize * height as usize];
+
+        Box::new(RecordedTexture {
+            id,
+            width,
+            height,
+            pixels: Mutex::new(pixels),
+        })
+    }
+
+    fn upload_texture(&self, texture: &Texture, bitmap: &Bitmap) -> Result<(), SglError> {
+        self.upload_texture_region(
+            texture,
+            0,
+            0,
+            bitmap,
+            (0, 0, bitmap.width(), bitmap.height()),
+        )
+    }
+
+    fn upload_texture_region(
+        &self,
+        texture: &Texture,
+        x: u32,
+        y: u32,
+        bitmap: &Bitmap,
+        source: (u32, u32, u32, u32),
+    ) -> Result<(), SglError> {
+        let recorded = Self::recorded(texture)?;
+        let mut pixels = recorded.pixels.lock().expect("texture pixels");
+
+        let (source_x, source_y, width, height) = source;
+        for row in 0..height as usize {
+            let from = (source_y as usize + row) * bitmap.width() as usize + source_x as usize;
+            let to = (y as usize + row) * recorded.width as usize + x as usize;
+
+            pixels[to..to + width as usize]
+                .copy_from_slice(&bitmap.pixels()[from..from + width as usize]);
+        }
+
+        Ok(())
+    }
+
+    fn download_texture(&self, texture: &Texture) -> Result<Bitmap, SglError> {
+        let recorded = Self::recorded(texture)?;
+        let pixels = recorded.pixels.lock().expect("texture pixels");
+
+        Bitmap::from_pixels(recorded.width, recorded.height, pixels.iter().copied())
+    }
+
+    fn submit(&mut self, render_commands: RenderCommands) -> RenderStats {
+        let stats = render_commands.stats();
+
+        let commands = render_commands
+            .commands
+            .iter()
+            .map(|command| match command {
+                RenderCommand::Draw {
+                    topology,
+                    texture,
+                    vertices,
+                    indices,
+                } => RecordedCommand::Draw {
+                    topology: *topology,
+                    texture: Self::texture_id(texture),
+                    vertices: render_commands.vertices
+                        [vertices.start as usize..vertices.end as usize]
+                        .to_vec(),
+                    indices: render_commands.indices[indices.start as usize..indices.end as usize]
+                        .to_vec(),
+                },
+                RenderCommand::DrawSdf { vertices, indices } => RecordedCommand::DrawSdf {
+                    vertices: render_commands.sdf_vertices
+                        [vertices.start as usize..vertices.end as usize]
+                        .to_vec(),
+                    indices: render_commands.indices[indices.start as usize..indices.end as usize]
+                        .to_vec(),
+                },
+                RenderCommand::View(view) => RecordedCommand::View(*view),
+            })
+            .collect();
+
+        self.frames.push(RecordedFrame {
+            clear_color: render_commands.clear_color,
+            commands,
+            stats,
+        });
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn textures_get_sequential_ids() {
+        let backend = RecordingBackend::new();
+        let a = Texture::new(1, 1, &backend, None);
+        let b = Texture::new(1, 1, &backend, None);
+
+        assert_eq!(Some(0), RecordingBackend::texture_id(&a));
+        assert_eq!(Some(1), RecordingBackend::texture_id(&b));
+    }
+
+    #[test]
+    fn records_submitted_frames() {
+        let mut backend = RecordingBackend::new();
+        let view = View::new([0.0, 0.0], 1280.0, 720.0);
+        let mut render_commands = RenderCommands::new(Some(Pixel::BLACK));
+        render_commands.push_view(view);
+
+        backend.submit(render_commands);
+
+        assert_eq!(1, backend.frames().len());
+        assert_eq!(Some(Pixel::BLACK), backend.frames()[0].clear_color);
+        assert_eq!(
+            vec![RecordedCommand::View(view)],
+            backend.frames()[0].commands
+        );
+    }
+}
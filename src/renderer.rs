@@ -1,285 +1,87 @@
-use std::{borrow::Cow, mem::size_of, ops::Range};
-
-use bytemuck::cast_slice;
-use sgl_math::Vec2;
-use wgpu::{
-    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
-    BindGroupLayoutEntry, BindingResource, BindingType, BlendState, Buffer, BufferAddress,
-    BufferBinding, BufferBindingType, BufferDescriptor, BufferSize, BufferUsages, Color,
-    ColorTargetState, ColorWrites, CommandEncoder, DynamicOffset, Face, FragmentState, FrontFace,
-    IndexFormat, LoadOp, MultisampleState, Operations, PipelineLayoutDescriptor, PolygonMode,
-    PrimitiveState, PrimitiveTopology, RenderPassColorAttachment, RenderPassDescriptor,
-    RenderPipeline, RenderPipelineDescriptor, SamplerBindingType, ShaderModuleDescriptor,
-    ShaderSource, ShaderStages, SurfaceError, TextureSampleType, TextureView, TextureViewDimension,
-    VertexState,
-};
+use sgl_math::{v2, Transform, Vec2};
 use winit::dpi::PhysicalSize;
 
 use crate::{
-    geometry::Vertex,
-    shape::{LineShape, RectangleShape},
-    Bitmap, GraphicsDevice, Pixel, Scene, SglError, Texture, View, Window,
+    backend::{RenderCommands, Topology},
+    debug_text::{debug_font, DebugText, GLYPH_SIZE},
+    sdf::SdfShape,
+    shape::{
+        EllipseShape, LineShape, PathShape, PointsShape, PolygonShape, PolylineShape,
+        RectangleShape,
+    },
+    Backend, Bitmap, BitmapFont, Canvas, FillRule, Font, Origin, Paint, Path, Pixel, PointStyle,
+    RenderStats, Scene, SglError, Sprite, Stroke, TextFont, TextLayout, TextStyle, Texture, Vertex,
+    View, Window,
 };
 
 pub struct Renderer {
-    physical_size: PhysicalSize<u32>,
     pixel_size: PhysicalSize<u32>,
-    vbo: Buffer,
-    ibo: Buffer,
-    view_ubo: Buffer,
-    view_ubo_stride: usize,
-    view_bind_group: BindGroup,
-    shape_bind_group_layout: BindGroupLayout,
-    triangle_list_pipeline: RenderPipeline,
-    triangle_strip_pipeline: RenderPipeline,
     default_texture: Texture,
+    debug_font: BitmapFont,
 }
 
 impl Renderer {
-    const MAX_INSTANCES: usize = 100_000;
-    const MAX_VERTICES: usize = Self::MAX_INSTANCES * 4; // Assume rectangles.
-    const MAX_INDICES: usize = Self::MAX_INSTANCES * 6; // Assume rectangles.
-    const MAX_VIEWS: usize = 20;
-
-    pub fn new(gpu: &GraphicsDevice, window: &Window) -> Result<Self, SglError> {
-        let physical_size = window.native_window.inner_size();
-        let pixel_size = window.pixel_size;
-
-        let vbo = gpu.device.create_buffer(&BufferDescriptor {
-            label: Some("sgl::vbo"),
-            size: (size_of::<Vertex>() * Self::MAX_VERTICES) as BufferAddress,
-            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let ibo = gpu.device.create_buffer(&BufferDescriptor {
-            label: Some("sgl::ibo"),
-            size: (size_of::<u32>() * Self::MAX_INDICES) as BufferAddress,
-            usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let view_ubo_stride = usize::max(
-            size_of::<[f32; 16]>(),
-            gpu.limits.min_uniform_buffer_offset_alignment as usize,
-        );
+    pub fn new(gpu: &impl Backend, window: &Window) -> Result<Self, SglError> {
+        // Low resolution windows are drawn at one pixel per unit and scaled up afterwards.
+        let pixel_size = match window.low_res {
+            Some(_) => PhysicalSize::new(1, 1),
+            None => window.pixel_size,
+        };
 
-        let view_ubo = gpu.device.create_buffer(&BufferDescriptor {
-            label: Some("sgl::ubo::view"),
-            size: view_ubo_stride as u64 * Self::MAX_VIEWS as u64,
-            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let view_bind_group_layout =
-            gpu.device
-                .create_bind_group_layout(&BindGroupLayoutDescriptor {
-                    label: Some("sgl::bind_group_layout::view"),
-                    entries: &[BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: ShaderStages::VERTEX,
-                        ty: BindingType::Buffer {
-                            ty: BufferBindingType::Uniform,
-                            has_dynamic_offset: true,
-                            min_binding_size: BufferSize::new(view_ubo_stride as u64),
-                        },
-                        count: None,
-                    }],
-                });
-
-        let view_bind_group = gpu.device.create_bind_group(&BindGroupDescriptor {
-            label: Some("sgl::bind_group::view"),
-            layout: &view_bind_group_layout,
-            entries: &[BindGroupEntry {
-                binding: 0,
-                resource: BindingResource::Buffer(BufferBinding {
-                    buffer: &view_ubo,
-                    offset: 0,
-                    size: BufferSize::new(view_ubo_stride as u64 * Self::MAX_VIEWS as u64),
-                }),
-            }],
-        });
-
-        let shape_bind_group_layout =
-            gpu.device
-                .create_bind_group_layout(&BindGroupLayoutDescriptor {
-                    label: Some("sgl::bind_group_layout::shape"),
-                    entries: &[
-                        BindGroupLayoutEntry {
-                            binding: 0,
-                            visibility: ShaderStages::FRAGMENT,
-                            ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                            count: None,
-                        },
-                        BindGroupLayoutEntry {
-                            binding: 1,
-                            visibility: ShaderStages::FRAGMENT,
-                            ty: BindingType::Texture {
-                                sample_type: TextureSampleType::Float { filterable: true },
-                                view_dimension: TextureViewDimension::D2,
-                                multisampled: false,
-                            },
-                            count: None,
-                        },
-                    ],
-                });
-
-        let shader_module = gpu.device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("sgl::shader_module"),
-            source: ShaderSource::Wgsl(Cow::Borrowed(SHADER)).into(),
-        });
-
-        let pipeline_layout = gpu
-            .device
-            .create_pipeline_layout(&PipelineLayoutDescriptor {
-                label: Some("sgl::pipeline_layout"),
-                bind_group_layouts: &[&view_bind_group_layout, &shape_bind_group_layout],
-                push_constant_ranges: &[],
-            });
-
-        let triangle_list_pipeline = gpu
-            .device
-            .create_render_pipeline(&RenderPipelineDescriptor {
-                label: Some("sgl::pipeline::triangle_list"),
-                layout: Some(&pipeline_layout),
-                vertex: VertexState {
-                    module: &shader_module,
-                    entry_point: "vs_main",
-                    buffers: &[Vertex::desc()],
-                },
-                fragment: Some(FragmentState {
-                    module: &shader_module,
-                    entry_point: "fs_main",
-                    targets: &[Some(ColorTargetState {
-                        format: gpu.surface_config.format,
-                        blend: Some(BlendState::ALPHA_BLENDING),
-                        write_mask: ColorWrites::ALL,
-                    })],
-                }),
-                primitive: PrimitiveState {
-                    topology: PrimitiveTopology::TriangleList,
-                    polygon_mode: PolygonMode::Fill,
-                    front_face: FrontFace::Ccw,
-                    strip_index_format: None,
-                    cull_mode: Some(Face::Back),
-                    conservative: false,
-                    unclipped_depth: false,
-                },
-                depth_stencil: None,
-                multisample: MultisampleState {
-                    count: 1,
-                    mask: !0,
-                    alpha_to_coverage_enabled: false,
-                },
-                multiview: None,
-            });
-
-        let triangle_strip_pipeline =
-            gpu.device
-                .create_render_pipeline(&RenderPipelineDescriptor {
-                    label: Some("sgl::pipeline::triangle_strip"),
-                    layout: Some(&pipeline_layout),
-                    vertex: VertexState {
-                        module: &shader_module,
-                        entry_point: "vs_main",
-                        buffers: &[Vertex::desc()],
-                    },
-                    fragment: Some(FragmentState {
-                        module: &shader_module,
-                        entry_point: "fs_main",
-                        targets: &[Some(ColorTargetState {
-                            format: gpu.surface_config.format,
-                            blend: Some(BlendState::ALPHA_BLENDING),
-                            write_mask: ColorWrites::ALL,
-                        })],
-                    }),
-                    primitive: PrimitiveState {
-                        topology: PrimitiveTopology::TriangleStrip,
-                        polygon_mode: PolygonMode::Fill,
-                        front_face: FrontFace::Ccw,
-                        strip_index_format: Some(IndexFormat::Uint32),
-                        cull_mode: Some(Face::Back),
-                        conservative: false,
-                        unclipped_depth: false,
-                    },
-                    depth_stencil: None,
-                    multisample: MultisampleState {
-                        count: 1,
-                        mask: !0,
-                        alpha_to_coverage_enabled: false,
-                    },
-                    multiview: None,
-                });
-
-        let default_texture = Texture::new(
-            1,
-            1,
-            gpu,
-            gpu.surface_config.format,
-            &shape_bind_group_layout,
-            Some("sgl::renderer::default_texture"),
-        );
+        Self::with_pixel_size(gpu, pixel_size)
+    }
+
+    pub(crate) fn with_pixel_size(
+        gpu: &impl Backend,
+        pixel_size: PhysicalSize<u32>,
+    ) -> Result<Self, SglError> {
+        let default_texture = Texture::new(1, 1, gpu, Some("sgl::renderer::default_texture"));
 
         default_texture.upload_to_gpu(gpu, &Bitmap::from_pixels(1, 1, [Pixel::WHITE])?)?;
 
         Ok(Self {
-            physical_size,
             pixel_size,
-            vbo,
-            ibo,
-            view_ubo,
-            view_ubo_stride,
-            view_bind_group,
-            shape_bind_group_layout,
-            triangle_list_pipeline,
-            triangle_strip_pipeline,
             default_texture,
+            debug_font: debug_font(gpu)?,
         })
     }
 
-    pub fn begin_scene(&self, window: &Window) -> Scene {
+    pub fn begin_scene(&self, window: &Window) -> Scene<'_> {
         Scene::new(window.view())
     }
 
-    pub fn end_scene(&self, scene: Scene, gpu: &mut GraphicsDevice) {
-        let render_commands = self.prepare(scene);
+    pub fn end_scene(&self, scene: Scene, gpu: &mut impl Backend) -> RenderStats {
+        let render_commands = self.prepare(scene, gpu);
 
-        let (frame, surface_view) = match gpu.get_frame() {
-            Ok((frame, surface_view)) => (frame, surface_view),
-            Err(SurfaceError::Lost | SurfaceError::Outdated) => {
-                let physical_size =
-                    PhysicalSize::new(gpu.surface_config.width, gpu.surface_config.height);
-                gpu.resize(physical_size);
-                return;
-            }
-            Err(SurfaceError::OutOfMemory) => {
-                log::error!("surface out of memory");
-                return;
-            }
-            Err(SurfaceError::Timeout) => {
-                log::warn!("surface timeout");
-                return;
-            }
-        };
-
-        let mut encoder = gpu.create_command_encoder();
-        self.render(gpu, render_commands, &surface_view, &mut encoder);
-        gpu.present(frame, encoder);
+        gpu.submit(render_commands)
     }
 
-    pub(crate) fn prepare<'draw>(&'draw self, scene: Scene<'draw>) -> RenderCommands<'draw> {
-        let mut render_commands = RenderCommands {
-            load_op: scene
-                .clear_color
-                .map_or(LoadOp::Load, |color| LoadOp::Clear(color.into())),
-            commands: Vec::new(),
-            data: Vec::new(),
+    /// Turn the scene into render commands. Glyphs rasterized on the way are uploaded to
+    /// their font atlases on `gpu`.
+    pub(crate) fn prepare<'draw>(
+        &'draw self,
+        scene: Scene<'draw>,
+        gpu: &impl Backend,
+    ) -> RenderCommands<'draw> {
+        let mut render_commands = RenderCommands::new(scene.clear_color);
+        let mut fonts: Vec<&Font> = Vec::new();
+
+        let pixel_size: Vec2 = (self.pixel_size.width, self.pixel_size.height).into();
+        let pixel_transform = Transform::scale(pixel_size);
+        let mut transform = pixel_transform;
+        let mut anti_aliased = false;
+        let mut y_up = false;
+
+        // Text is laid out with y down, so it's flipped over the line at `y` to read upright in
+        // views with y up.
+        let upright = |transform: Transform, y_up: bool, y: f32| {
+            if y_up {
+                transform * Transform::translate(v2(0.0, 2.0 * y)) * Transform::scale(v2(1.0, -1.0))
+            } else {
+                transform
+            }
         };
 
-        let mut vbo_offset = 0;
-        let mut ibo_offset = 0;
-        let mut view_ubo_offset = 0;
-
         for draw_command in scene.draw_commands.into_iter() {
             match draw_command {
                 DrawCommand::Line {
@@ -288,35 +90,29 @@ impl Renderer {
                     color,
                     thickness,
                 } => {
+                    if anti_aliased {
+                        let line = SdfShape::line(from, to, thickness);
+                        let (vertices, indices) = line.fill_geometry(color, transform);
+
+                        render_commands.push_sdf_draw(vertices, indices);
+                        continue;
+                    }
+
                     let line = LineShape::new(from, to);
-                    let pixel_size = (self.pixel_size.width, self.pixel_size.height).into();
-                    let (vertices, indices) = line.fill_geometry(thickness, color, pixel_size);
-
-                    let vertices_size = size_of::<Vertex>() as u64 * vertices.len() as u64;
-                    let indices_size = size_of::<u32>() as u64 * indices.len() as u64;
-
-                    render_commands.commands.push(RenderCommand::Line {
-                        pipeline: &self.triangle_strip_pipeline,
-                        bind_group: &self.default_texture.bind_group,
-                        vbo_bounds: vbo_offset..vbo_offset + vertices_size,
-                        ibo_bounds: ibo_offset..ibo_offset + indices_size,
-                        index_count: indices.len() as u32,
-                    });
-
-                    render_commands.data.push(RenderData::Line {
-                        vbo_offset,
-                        ibo_offset,
+                    let (vertices, indices) = line.fill_geometry(thickness, color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleStrip,
+                        &self.default_texture,
                         vertices,
                         indices,
-                    });
-
-                    vbo_offset += vertices_size;
-                    ibo_offset += indices_size;
+                    );
                 }
 
                 DrawCommand::Rect {
                     from,
                     to,
+                    radii,
                     color,
                     thickness,
                 } => {
@@ -324,57 +120,170 @@ impl Renderer {
                         continue;
                     }
 
-                    let rect = RectangleShape::new(from, to);
-                    let pixel_size = (self.pixel_size.width, self.pixel_size.height).into();
-                    let (vertices, indices) = rect.outline_geometry(thickness, color, pixel_size);
-
-                    let vertices_size = size_of::<Vertex>() as u64 * vertices.len() as u64;
-                    let indices_size = size_of::<u32>() as u64 * indices.len() as u64;
-
-                    render_commands.commands.push(RenderCommand::Rect {
-                        pipeline: &self.triangle_strip_pipeline,
-                        bind_group: &self.default_texture.bind_group,
-                        vbo_bounds: vbo_offset..vbo_offset + vertices_size,
-                        ibo_bounds: ibo_offset..ibo_offset + indices_size,
-                        index_count: indices.len() as u32,
-                    });
-
-                    render_commands.data.push(RenderData::Rect {
-                        vbo_offset,
-                        ibo_offset,
+                    if anti_aliased {
+                        let rect = SdfShape::rect(from, to, radii);
+                        let (vertices, indices) =
+                            rect.outline_geometry(thickness, color, transform);
+
+                        render_commands.push_sdf_draw(vertices, indices);
+                        continue;
+                    }
+
+                    let rect = RectangleShape::rounded(from, to, radii);
+                    let (vertices, indices) = rect.outline_geometry(thickness, color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleStrip,
+                        &self.default_texture,
                         vertices,
                         indices,
-                    });
+                    );
+                }
+
+                DrawCommand::RectFilled {
+                    from,
+                    to,
+                    radii,
+                    paint,
+                } => {
+                    if let (true, Paint::Solid(color)) = (anti_aliased, &paint) {
+                        let rect = SdfShape::rect(from, to, radii);
+                        let (vertices, indices) = rect.fill_geometry(*color, transform);
 
-                    vbo_offset += vertices_size;
-                    ibo_offset += indices_size;
+                        render_commands.push_sdf_draw(vertices, indices);
+                        continue;
+                    }
+
+                    let rect = RectangleShape::rounded(from, to, radii);
+                    let (vertices, indices) =
+                        paint.fill(rect.fill_geometry(Pixel::WHITE, transform), transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
                 }
 
-                DrawCommand::RectFilled { from, to, color } => {
-                    let rect = RectangleShape::new(from, to);
-                    let pixel_size = (self.pixel_size.width, self.pixel_size.height).into();
-                    let (vertices, indices) = rect.fill_geometry(color, pixel_size);
-
-                    let vertices_size = size_of::<Vertex>() as u64 * vertices.len() as u64;
-                    let indices_size = size_of::<u32>() as u64 * indices.len() as u64;
-
-                    render_commands.commands.push(RenderCommand::RectFilled {
-                        pipeline: &self.triangle_list_pipeline,
-                        bind_group: &self.default_texture.bind_group,
-                        vbo_bounds: vbo_offset..vbo_offset + vertices_size,
-                        ibo_bounds: ibo_offset..ibo_offset + indices_size,
-                        index_count: indices.len() as u32,
-                    });
-
-                    render_commands.data.push(RenderData::RectFilled {
-                        vbo_offset,
-                        ibo_offset,
+                DrawCommand::Points {
+                    points,
+                    size,
+                    style,
+                } => {
+                    if points.is_empty() || size == 0.0 {
+                        continue;
+                    }
+
+                    if anti_aliased {
+                        let mut vertices = Vec::with_capacity(points.len() * 4);
+                        let mut indices = Vec::with_capacity(points.len() * 6);
+
+                        let half = size.abs() / 2.0;
+
+                        for (position, color) in points {
+                            let shape = match style {
+                                PointStyle::Square => SdfShape::rect(
+                                    position - v2(half, half),
+                                    position + v2(half, half),
+                                    [0.0; 4],
+                                ),
+                                PointStyle::Round => SdfShape::circle(position, half),
+                            };
+                            let (quad, quad_indices) = shape.fill_geometry(color, transform);
+
+                            let first = vertices.len() as u32;
+                            vertices.extend(quad);
+                            indices.extend(quad_indices.into_iter().map(|i| first + i));
+                        }
+
+                        render_commands.push_sdf_draw(vertices, indices);
+                        continue;
+                    }
+
+                    let shape = PointsShape::new(&points, size);
+                    let (vertices, indices) = shape.fill_geometry(style, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
                         vertices,
                         indices,
-                    });
+                    );
+                }
 
-                    vbo_offset += vertices_size;
-                    ibo_offset += indices_size;
+                DrawCommand::Text {
+                    font,
+                    text,
+                    from,
+                    to,
+                    style,
+                } => {
+                    let transform = upright(transform, y_up, (from.y + to.y) / 2.0);
+                    let (vertices, indices) =
+                        font.text_geometry(&text, from, to, &style, transform);
+
+                    if !fonts.iter().any(|f| std::ptr::eq(*f, font)) {
+                        fonts.push(font);
+                    }
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        font.atlas(),
+                        vertices,
+                        indices,
+                    );
+                }
+
+                DrawCommand::BitmapText {
+                    font,
+                    text,
+                    from,
+                    to,
+                    style,
+                } => {
+                    let transform = upright(transform, y_up, (from.y + to.y) / 2.0);
+                    for (page, vertices, indices) in
+                        font.text_geometry(&text, from, to, &style, transform)
+                    {
+                        render_commands.push_draw(Topology::TriangleList, page, vertices, indices);
+                    }
+                }
+
+                DrawCommand::TextLayout {
+                    layout,
+                    position,
+                    visible,
+                } => {
+                    if let TextFont::Vector(font) = layout.font() {
+                        if !fonts.iter().any(|f| std::ptr::eq(*f, font)) {
+                            fonts.push(font);
+                        }
+                    }
+
+                    let transform = upright(transform, y_up, position.y);
+                    for (texture, vertices, indices) in
+                        layout.geometry(position, visible, y_up, transform)
+                    {
+                        render_commands.push_draw(
+                            Topology::TriangleList,
+                            texture,
+                            vertices,
+                            indices,
+                        );
+                    }
+                }
+
+                DrawCommand::RectCorners { from, to, colors } => {
+                    let rect = RectangleShape::new(from, to);
+                    let (vertices, indices) = rect.corner_fill_geometry(colors, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
                 }
 
                 DrawCommand::RectTextured {
@@ -384,211 +293,360 @@ impl Renderer {
                     sub_coords,
                 } => {
                     let rect = RectangleShape::new(from, to);
-                    let pixel_size = (self.pixel_size.width, self.pixel_size.height).into();
                     let (vertices, indices) =
-                        rect.texture_geometry(texture, sub_coords, pixel_size);
-
-                    let vertices_size = size_of::<Vertex>() as u64 * vertices.len() as u64;
-                    let indices_size = size_of::<u32>() as u64 * indices.len() as u64;
-
-                    render_commands.commands.push(RenderCommand::RectTextured {
-                        pipeline: &self.triangle_list_pipeline,
-                        bind_group: &texture.bind_group,
-                        vbo_bounds: vbo_offset..vbo_offset + vertices_size,
-                        ibo_bounds: ibo_offset..ibo_offset + indices_size,
-                        index_count: indices.len() as u32,
-                    });
-
-                    render_commands.data.push(RenderData::RectTextured {
-                        vbo_offset,
-                        ibo_offset,
-                        vertices,
-                        indices,
-                    });
+                        rect.texture_geometry(texture, sub_coords, Pixel::WHITE, y_up, transform);
 
-                    vbo_offset += vertices_size;
-                    ibo_offset += indices_size;
+                    render_commands.push_draw(Topology::TriangleList, texture, vertices, indices);
                 }
 
-                DrawCommand::View(view) => {
-                    render_commands.commands.push(RenderCommand::View {
-                        view,
-                        offset: view_ubo_offset,
-                    });
+                DrawCommand::Canvas { from, to, canvas } => {
+                    if let Err(e) = canvas.upload_to_gpu(gpu) {
+                        log::warn!("failed to upload canvas: {e}");
+                    }
 
-                    render_commands.data.push(RenderData::View {
-                        offset: view_ubo_offset as BufferAddress,
-                        transform: view.transform(),
-                    });
+                    // The scene transform is left out, the canvas covers the window.
+                    let texture = canvas.texture();
+                    let rect = RectangleShape::new(from, to);
+                    let (vertices, indices) =
+                        rect.texture_geometry(texture, None, Pixel::WHITE, y_up, pixel_transform);
 
-                    view_ubo_offset += self.view_ubo_stride as DynamicOffset;
+                    render_commands.push_draw(Topology::TriangleList, texture, vertices, indices);
                 }
-            }
-        }
 
-        render_commands
-    }
+                DrawCommand::Ellipse {
+                    center,
+                    radii,
+                    color,
+                    thickness,
+                } => {
+                    if thickness <= 0.0 {
+                        continue;
+                    }
 
-    pub(crate) fn render(
-        &self,
-        gpu: &mut GraphicsDevice,
-        render_commands: RenderCommands,
-        surface_view: &TextureView,
-        encoder: &mut CommandEncoder,
-    ) {
-        for render_data in render_commands.data {
-            match render_data {
-                RenderData::Line {
-                    vbo_offset,
-                    ibo_offset,
-                    vertices,
-                    indices,
+                    if anti_aliased && radii.x == radii.y {
+                        let circle = SdfShape::circle(center, radii.x);
+                        let (vertices, indices) =
+                            circle.outline_geometry(thickness, color, transform);
+
+                        render_commands.push_sdf_draw(vertices, indices);
+                        continue;
+                    }
+
+                    let ellipse = EllipseShape::new(center, radii);
+                    let (vertices, indices) = ellipse.outline_geometry(thickness, color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleStrip,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
                 }
-                | RenderData::Rect {
-                    vbo_offset,
-                    ibo_offset,
-                    vertices,
-                    indices,
+
+                DrawCommand::EllipseFilled {
+                    center,
+                    radii,
+                    paint,
+                } => {
+                    if let (true, Paint::Solid(color)) =
+                        (anti_aliased && radii.x == radii.y, &paint)
+                    {
+                        let circle = SdfShape::circle(center, radii.x);
+                        let (vertices, indices) = circle.fill_geometry(*color, transform);
+
+                        render_commands.push_sdf_draw(vertices, indices);
+                        continue;
+                    }
+
+                    let ellipse = EllipseShape::new(center, radii);
+                    let (vertices, indices) =
+                        paint.fill(ellipse.fill_geometry(Pixel::WHITE, transform), transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
                 }
-                | RenderData::RectFilled {
-                    vbo_offset,
-                    ibo_offset,
-                    vertices,
-                    indices,
+
+                DrawCommand::Arc {
+                    center,
+                    radii,
+                    start_angle,
+                    sweep_angle,
+                    color,
+                    thickness,
+                } => {
+                    if thickness <= 0.0 {
+                        continue;
+                    }
+
+                    let arc = EllipseShape::arc(center, radii, start_angle, sweep_angle);
+                    let (vertices, indices) = arc.outline_geometry(thickness, color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleStrip,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
                 }
-                | RenderData::RectTextured {
-                    vbo_offset,
-                    ibo_offset,
-                    vertices,
-                    indices,
+
+                DrawCommand::Sector {
+                    center,
+                    radii,
+                    start_angle,
+                    sweep_angle,
+                    color,
+                    thickness,
                 } => {
-                    gpu.staging_belt
-                        .write_buffer(
-                            encoder,
-                            &self.vbo,
-                            vbo_offset,
-                            BufferSize::new(size_of::<Vertex>() as u64 * vertices.len() as u64)
-                                .expect("size must be non-zero"),
-                            &gpu.device,
-                        )
-                        .copy_from_slice(cast_slice(&vertices));
-
-                    gpu.staging_belt
-                        .write_buffer(
-                            encoder,
-                            &self.ibo,
-                            ibo_offset,
-                            BufferSize::new(size_of::<u32>() as u64 * indices.len() as u64)
-                                .expect("size must be non-zero"),
-                            &gpu.device,
-                        )
-                        .copy_from_slice(cast_slice(&indices));
+                    if thickness <= 0.0 {
+                        continue;
+                    }
+
+                    let sector = EllipseShape::arc(center, radii, start_angle, sweep_angle);
+                    let (vertices, indices) =
+                        sector.sector_outline_geometry(thickness, color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleStrip,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
                 }
 
-                RenderData::View { offset, transform } => {
-                    gpu.staging_belt
-                        .write_buffer(
-                            encoder,
-                            &self.view_ubo,
-                            offset,
-                            BufferSize::new(size_of::<[f32; 16]>() as u64)
-                                .expect("size must be non-zero"),
-                            &gpu.device,
-                        )
-                        .copy_from_slice(cast_slice(&[transform]));
+                DrawCommand::SectorFilled {
+                    center,
+                    radii,
+                    start_angle,
+                    sweep_angle,
+                    color,
+                } => {
+                    let sector = EllipseShape::arc(center, radii, start_angle, sweep_angle);
+                    let (vertices, indices) = sector.fill_geometry(color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
                 }
-            }
-        }
 
-        let color_attachment = RenderPassColorAttachment {
-            view: surface_view,
-            ops: Operations {
-                load: render_commands.load_op,
-                store: true,
-            },
-            resolve_target: None,
-        };
+                DrawCommand::Polyline {
+                    points,
+                    closed,
+                    color,
+                    stroke,
+                } => {
+                    let polyline = PolylineShape::new(&points, closed);
+                    let (vertices, indices) = polyline.stroke_geometry(&stroke, color, transform);
 
-        {
-            let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
-                label: Some("sgl::render_pass"),
-                color_attachments: &[Some(color_attachment)],
-                depth_stencil_attachment: None,
-            });
-
-            for render_command in render_commands.commands {
-                match render_command {
-                    RenderCommand::Line {
-                        pipeline,
-                        bind_group,
-                        vbo_bounds,
-                        ibo_bounds,
-                        index_count,
-                    }
-                    | RenderCommand::Rect {
-                        pipeline,
-                        bind_group,
-                        vbo_bounds,
-                        ibo_bounds,
-                        index_count,
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
+                }
+
+                DrawCommand::Polygon {
+                    points,
+                    holes,
+                    color,
+                    thickness,
+                } => {
+                    if thickness <= 0.0 {
+                        continue;
                     }
-                    | RenderCommand::RectFilled {
-                        pipeline,
-                        bind_group,
-                        vbo_bounds,
-                        ibo_bounds,
-                        index_count,
+
+                    let polygon = PolygonShape::new(&points, &holes);
+
+                    for (vertices, indices) in polygon.outline_geometry(thickness, color, transform)
+                    {
+                        render_commands.push_draw(
+                            Topology::TriangleStrip,
+                            &self.default_texture,
+                            vertices,
+                            indices,
+                        );
                     }
-                    | RenderCommand::RectTextured {
-                        pipeline,
-                        bind_group,
-                        vbo_bounds,
-                        ibo_bounds,
-                        index_count,
-                    } => {
-                        rpass.set_pipeline(pipeline);
-
-                        rpass.set_bind_group(1, bind_group, &[]);
-
-                        rpass.set_vertex_buffer(0, self.vbo.slice(vbo_bounds));
-                        rpass.set_index_buffer(self.ibo.slice(ibo_bounds), IndexFormat::Uint32);
-                        rpass.draw_indexed(0..index_count, 0, 0..1)
+                }
+
+                DrawCommand::PolygonFilled {
+                    points,
+                    holes,
+                    color,
+                } => {
+                    let polygon = PolygonShape::new(&points, &holes);
+                    let (vertices, indices) = polygon.fill_geometry(color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
+                }
+
+                DrawCommand::Path {
+                    path,
+                    color,
+                    stroke,
+                } => {
+                    let shape = PathShape::new(&path);
+                    let (vertices, indices) = shape.stroke_geometry(&stroke, color, transform);
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
+                }
+
+                DrawCommand::PathFilled { path, paint, rule } => {
+                    let shape = PathShape::new(&path);
+                    let (vertices, indices) = paint.fill(
+                        shape.fill_geometry(rule, Pixel::WHITE, transform),
+                        transform,
+                    );
+
+                    render_commands.push_draw(
+                        Topology::TriangleList,
+                        &self.default_texture,
+                        vertices,
+                        indices,
+                    );
+                }
+
+                DrawCommand::Sprite { texture, sprite } => {
+                    let texture_size = v2(texture.width() as f32, texture.height() as f32);
+                    let size = sprite.size(texture_size);
+
+                    let rect = RectangleShape::new(-sprite.origin, size - sprite.origin);
+                    let (vertices, indices) = rect.texture_geometry(
+                        texture,
+                        Some(sprite.sub_coords(texture_size)),
+                        sprite.color,
+                        y_up,
+                        transform * sprite.transform(),
+                    );
+
+                    render_commands.push_draw(Topology::TriangleList, texture, vertices, indices);
+                }
+
+                DrawCommand::Mesh {
+                    mut vertices,
+                    indices,
+                    texture,
+                    topology,
+                } => {
+                    // Strips may restart with the largest index, lists can't.
+                    let restarts = topology == Topology::TriangleStrip;
+                    let vertex_count = vertices.len() as u32;
+                    if let Some(index) = indices
+                        .iter()
+                        .find(|i| **i >= vertex_count && !(restarts && **i == u32::MAX))
+                    {
+                        log::warn!(
+                            "skipping mesh with index {index} out of range of {vertex_count} vertices"
+                        );
+                        continue;
                     }
 
-                    RenderCommand::View { view, offset } => {
-                        let left = view.width() * view.viewport_left();
-                        let top = view.height() * view.viewport_top();
-                        let right = view.width() * view.viewport_right();
-                        let bottom = view.height() * view.viewport_bottom();
-                        rpass.set_viewport(left, bottom, right, top, 0.0, 1.0);
-                        rpass.set_scissor_rect(
-                            left as u32,
-                            bottom as u32,
-                            right as u32,
-                            top as u32,
+                    if topology == Topology::TriangleList && !indices.len().is_multiple_of(3) {
+                        log::warn!(
+                            "skipping triangle list mesh with {} indices, not a multiple of 3",
+                            indices.len()
                         );
+                        continue;
+                    }
 
-                        rpass.set_bind_group(0, &self.view_bind_group, &[offset]);
+                    for vertex in vertices.iter_mut() {
+                        vertex.coords = transform
+                            .transform_point(v2(vertex.coords[0], vertex.coords[1]))
+                            .to_array();
                     }
+
+                    render_commands.push_draw(
+                        topology,
+                        texture.unwrap_or(&self.default_texture),
+                        vertices,
+                        indices,
+                    );
+                }
+
+                DrawCommand::View(view) => {
+                    y_up = view.y_up();
+                    render_commands.push_view(view.scaled(pixel_size));
+                }
+
+                DrawCommand::Transform(scene_transform) => {
+                    transform = pixel_transform * scene_transform;
+                }
+
+                DrawCommand::AntiAliased(enabled) => {
+                    anti_aliased = enabled;
                 }
             }
         }
+
+        if !scene.debug_text.is_empty() {
+            let (width, height) = (scene.screen_view.width(), scene.screen_view.height());
+            let screen = View::from_origin(Origin::TopLeft, width, height);
+
+            render_commands.push_view(screen.scaled(pixel_size));
+        }
+
+        for DebugText {
+            text,
+            position,
+            color,
+        } in scene.debug_text
+        {
+            let lines = text.split('\n');
+            let columns = lines.clone().map(|line| line.chars().count()).max();
+            let size = v2(columns.unwrap_or(0) as f32, lines.count() as f32) * GLYPH_SIZE;
+
+            let background =
+                RectangleShape::new(position - v2(1.0, 1.0), position + size + v2(1.0, 1.0));
+            let (vertices, indices) =
+                background.fill_geometry(Pixel::rgba(0x00, 0x00, 0x00, 0x99), pixel_transform);
+            render_commands.push_draw(
+                Topology::TriangleList,
+                &self.default_texture,
+                vertices,
+                indices,
+            );
+
+            let style = TextStyle::new(GLYPH_SIZE, color);
+            for (page, vertices, indices) in
+                self.debug_font
+                    .text_geometry(&text, position, position, &style, pixel_transform)
+            {
+                render_commands.push_draw(Topology::TriangleList, page, vertices, indices);
+            }
+        }
+
+        for font in fonts {
+            if let Err(e) = font.upload_atlas(gpu) {
+                log::warn!("failed to upload font atlas: {e}");
+            }
+        }
+
+        render_commands
     }
 
     pub fn create_texture(
         &self,
-        gpu: &GraphicsDevice,
+        gpu: &impl Backend,
         bitmap: &Bitmap,
         label: Option<&str>,
     ) -> Result<Texture, SglError> {
-        let texture = Texture::new(
-            bitmap.width(),
-            bitmap.height(),
-            gpu,
-            gpu.surface_config.format,
-            &self.shape_bind_group_layout,
-            label,
-        );
+        let texture = Texture::new(bitmap.width(), bitmap.height(), gpu, label);
 
         texture.upload_to_gpu(gpu, bitmap)?;
 
@@ -596,149 +654,650 @@ impl Renderer {
     }
 }
 
-pub(crate) struct RenderCommands<'draw> {
-    load_op: LoadOp<Color>,
-    commands: Vec<RenderCommand<'draw>>,
-    data: Vec<RenderData>,
-}
-
-enum RenderData {
+#[derive(Debug)]
+pub(crate) enum DrawCommand<'scene> {
     Line {
-        vbo_offset: BufferAddress,
-        ibo_offset: BufferAddress,
-        vertices: Vec<Vertex>,
-        indices: Vec<u32>,
+        from: Vec2,
+        to: Vec2,
+        color: Pixel,
+        thickness: f32,
     },
     Rect {
-        vbo_offset: BufferAddress,
-        ibo_offset: BufferAddress,
-        vertices: Vec<Vertex>,
-        indices: Vec<u32>,
+        from: Vec2,
+        to: Vec2,
+        radii: [f32; 4],
+        color: Pixel,
+        thickness: f32,
     },
     RectFilled {
-        vbo_offset: BufferAddress,
-        ibo_offset: BufferAddress,
-        vertices: Vec<Vertex>,
-        indices: Vec<u32>,
+        from: Vec2,
+        to: Vec2,
+        radii: [f32; 4],
+        paint: Paint,
     },
-    RectTextured {
-        vbo_offset: BufferAddress,
-        ibo_offset: BufferAddress,
-        vertices: Vec<Vertex>,
-        indices: Vec<u32>,
+    RectCorners {
+        from: Vec2,
+        to: Vec2,
+        colors: [Pixel; 4],
     },
-    View {
-        offset: BufferAddress,
-        transform: [f32; 16],
+    Text {
+        font: &'scene Font,
+        text: String,
+        from: Vec2,
+        to: Vec2,
+        style: TextStyle,
     },
-}
-
-enum RenderCommand<'draw> {
-    Line {
-        pipeline: &'draw RenderPipeline,
-        bind_group: &'draw BindGroup,
-        vbo_bounds: Range<BufferAddress>,
-        ibo_bounds: Range<BufferAddress>,
-        index_count: u32,
+    BitmapText {
+        font: &'scene BitmapFont,
+        text: String,
+        from: Vec2,
+        to: Vec2,
+        style: TextStyle,
     },
-    Rect {
-        pipeline: &'draw RenderPipeline,
-        bind_group: &'draw BindGroup,
-        vbo_bounds: Range<BufferAddress>,
-        ibo_bounds: Range<BufferAddress>,
-        index_count: u32,
+    TextLayout {
+        layout: &'scene TextLayout<'scene>,
+        position: Vec2,
+        visible: usize,
     },
-    RectFilled {
-        pipeline: &'draw RenderPipeline,
-        bind_group: &'draw BindGroup,
-        vbo_bounds: Range<BufferAddress>,
-        ibo_bounds: Range<BufferAddress>,
-        index_count: u32,
+    Points {
+        points: Vec<(Vec2, Pixel)>,
+        size: f32,
+        style: PointStyle,
     },
     RectTextured {
-        pipeline: &'draw RenderPipeline,
-        bind_group: &'draw BindGroup,
-        vbo_bounds: Range<BufferAddress>,
-        ibo_bounds: Range<BufferAddress>,
-        index_count: u32,
+        from: Vec2,
+        to: Vec2,
+        texture: &'scene Texture,
+        sub_coords: Option<(Vec2, Vec2)>,
+    },
+    Canvas {
+        from: Vec2,
+        to: Vec2,
+        canvas: &'scene Canvas,
+    },
+    Ellipse {
+        center: Vec2,
+        radii: Vec2,
+        color: Pixel,
+        thickness: f32,
+    },
+    EllipseFilled {
+        center: Vec2,
+        radii: Vec2,
+        paint: Paint,
+    },
+    Arc {
+        center: Vec2,
+        radii: Vec2,
+        start_angle: f32,
+        sweep_angle: f32,
+        color: Pixel,
+        thickness: f32,
+    },
+    Sector {
+        center: Vec2,
+        radii: Vec2,
+        start_angle: f32,
+        sweep_angle: f32,
+        color: Pixel,
+        thickness: f32,
+    },
+    SectorFilled {
+        center: Vec2,
+        radii: Vec2,
+        start_angle: f32,
+        sweep_angle: f32,
+        color: Pixel,
+    },
+    Polyline {
+        points: Vec<Vec2>,
+        closed: bool,
+        color: Pixel,
+        stroke: Stroke,
+    },
+    Polygon {
+        points: Vec<Vec2>,
+        holes: Vec<Vec<Vec2>>,
+        color: Pixel,
+        thickness: f32,
+    },
+    PolygonFilled {
+        points: Vec<Vec2>,
+        holes: Vec<Vec<Vec2>>,
+        color: Pixel,
+    },
+    Path {
+        path: Path,
+        color: Pixel,
+        stroke: Stroke,
+    },
+    PathFilled {
+        path: Path,
+        paint: Paint,
+        rule: FillRule,
+    },
+    Sprite {
+        texture: &'scene Texture,
+        sprite: Sprite,
     },
-    View {
-        view: View,
-        offset: DynamicOffset,
+    Mesh {
+        vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+        texture: Option<&'scene Texture>,
+        topology: Topology,
     },
+    View(View),
+    Transform(Transform),
+    AntiAliased(bool),
 }
 
-static SHADER: &str = r"
-// Vertex
+#[cfg(test)]
+mod tests {
+    use sgl_math::Aabb;
 
-@group(0) @binding(0)
-var<uniform> scene_transform: mat4x4<f32>;
+    use crate::{RecordedCommand, RecordingBackend};
 
-struct VsIn {
-    @location(0) coords: vec2<f32>,
-    @location(1) tex_coords: vec2<f32>,
-    @location(2) fill_color: vec4<f32>,
-};
+    use super::*;
 
-struct VsOut {
-    @builtin(position) position: vec4<f32>,
-    @location(0) tex_coords: vec2<f32>,
-    @location(1) fill_color: vec4<f32>,
-};
+    fn renderer(backend: &RecordingBackend) -> Renderer {
+        Renderer::with_pixel_size(backend, PhysicalSize::new(1, 1)).expect("renderer")
+    }
 
-@vertex
-fn vs_main(in: VsIn) -> VsOut {
-    let position = scene_transform * vec4<f32>(in.coords, 0.0, 1.0);
+    #[test]
+    fn end_scene_submits_scene_to_backend() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+
+        let view = View::new([0.0, 0.0], 1280.0, 720.0);
+        let mut scene = Scene::new(view);
+        scene.clear(Pixel::BLACK);
+        scene.draw_filled_rect([0.0, 0.0], [10.0, 10.0], Pixel::RED);
+        renderer.end_scene(scene, &mut backend);
+
+        let frame = &backend.frames()[0];
+        assert_eq!(Some(Pixel::BLACK), frame.clear_color);
+        assert_eq!(RecordedCommand::View(view), frame.commands[0]);
+
+        if let RecordedCommand::Draw {
+            topology,
+            texture,
+            vertices,
+            indices,
+        } = &frame.commands[1]
+        {
+            assert_eq!(Topology::TriangleList, *topology);
+            assert_eq!(
+                RecordingBackend::texture_id(&renderer.default_texture),
+                *texture
+            );
+            assert_eq!(4, vertices.len());
+            assert_eq!(&vec![0, 1, 3, 3, 1, 2], indices);
+        } else {
+            panic!("expected draw command");
+        }
+    }
 
-    return VsOut(position, in.tex_coords, in.fill_color);
-}
+    #[test]
+    fn textured_rect_draws_with_its_texture() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+        let texture = Texture::new(2, 2, &backend, None);
 
-struct FsIn {
-    @location(0) tex_coords: vec2<f32>,
-    @location(1) fill_color: vec4<f32>,
-};
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_textured_rect([0.0, 0.0], [10.0, 10.0], &texture);
+        renderer.end_scene(scene, &mut backend);
 
-struct FsOut {
-    @location(0) color: vec4<f32>,
-};
+        if let RecordedCommand::Draw { texture: id, .. } = &backend.frames()[0].commands[1] {
+            assert_eq!(RecordingBackend::texture_id(&texture), *id);
+        } else {
+            panic!("expected draw command");
+        }
+    }
 
-@group(1) @binding(0)
-var texture_sampler: sampler;
-@group(1) @binding(1)
-var texture: texture_2d<f32>;
+    #[test]
+    fn textures_and_text_stay_upright_with_y_up() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+        let texture = Texture::new(4, 4, &backend, None);
+        let font = Font::from_bytes(
+            &backend,
+            include_bytes!("../examples/assets/FiraSans-Regular.ttf"),
+        )
+        .unwrap();
+
+        let mut scene = Scene::new(View::from_origin(Origin::BottomLeft, 1280.0, 720.0));
+        scene.draw_textured_rect_ext([0.0, 0.0], [10.0, 10.0], &texture, [0.0, 0.0], [4.0, 2.0]);
+        scene.draw_text(&font, "Hi", [100.0, 100.0], 16.0, Pixel::WHITE);
+        renderer.end_scene(scene, &mut backend);
+
+        let commands = &backend.frames()[0].commands;
+        let RecordedCommand::Draw { vertices, .. } = &commands[1] else {
+            panic!("expected draw command");
+        };
+        // The bottom of the rect shows the bottom of the texture, the region counting up from it.
+        let bottom = vertices.iter().find(|v| v.coords == [0.0, 0.0]).unwrap();
+        let top = vertices.iter().find(|v| v.coords == [0.0, 10.0]).unwrap();
+        assert_eq!([0.0, 1.0], bottom.tex_coords);
+        assert_eq!([0.0, 0.5], top.tex_coords);
+
+        let RecordedCommand::Draw { vertices, .. } = &commands[2] else {
+            panic!("expected draw command");
+        };
+        // Text hangs down from its position, which is now towards smaller y.
+        assert!(vertices.iter().all(|v| v.coords[1] <= 100.0));
+        assert!(vertices.iter().any(|v| v.coords[1] < 90.0));
+    }
 
-@fragment
-fn fs_main(in: FsIn) -> FsOut {
-    let color = textureSample(texture, texture_sampler, in.tex_coords) * in.fill_color;
+    #[test]
+    fn rect_without_thickness_is_not_drawn() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
 
-    return FsOut(color);
-}
-";
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_rect([0.0, 0.0], [10.0, 10.0], Pixel::RED, 0.0);
+        renderer.end_scene(scene, &mut backend);
 
-#[derive(Debug)]
-pub(crate) enum DrawCommand<'scene> {
-    Line {
-        from: Vec2,
-        to: Vec2,
-        color: Pixel,
-        thickness: f32,
-    },
-    Rect {
-        from: Vec2,
-        to: Vec2,
-        color: Pixel,
-        thickness: f32,
-    },
-    RectFilled {
-        from: Vec2,
-        to: Vec2,
-        color: Pixel,
-    },
-    RectTextured {
-        from: Vec2,
-        to: Vec2,
-        texture: &'scene Texture,
-        sub_coords: Option<(Vec2, Vec2)>,
-    },
-    View(View),
+        assert_eq!(1, backend.frames()[0].commands.len());
+    }
+
+    #[test]
+    fn scene_transform_is_applied_before_pixel_size() {
+        let mut backend = RecordingBackend::new();
+        let renderer = Renderer::with_pixel_size(&backend, PhysicalSize::new(2, 2)).unwrap();
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.translate([10.0, 20.0]);
+        scene.draw_filled_rect([0.0, 0.0], [5.0, 5.0], Pixel::RED);
+        renderer.end_scene(scene, &mut backend);
+
+        if let RecordedCommand::Draw { vertices, .. } = &backend.frames()[0].commands[1] {
+            assert_eq!([20.0, 40.0], vertices[0].coords);
+            assert_eq!([30.0, 50.0], vertices[2].coords);
+        } else {
+            panic!("expected draw command");
+        }
+    }
+
+    #[test]
+    fn sprite_is_placed_around_its_origin() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+        let texture = Texture::new(4, 2, &backend, None);
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_sprite(
+            &texture,
+            Sprite::new([100.0, 100.0])
+                .with_origin([2.0, 1.0])
+                .with_scale([2.0, 3.0])
+                .with_flip(true, false)
+                .with_color(Pixel::RED),
+        );
+        renderer.end_scene(scene, &mut backend);
+
+        if let RecordedCommand::Draw { vertices, .. } = &backend.frames()[0].commands[1] {
+            assert_eq!([96.0, 97.0], vertices[0].coords);
+            assert_eq!([104.0, 103.0], vertices[2].coords);
+            assert_eq!([1.0, 0.0], vertices[0].tex_coords);
+            assert_eq!([0.0, 1.0], vertices[2].tex_coords);
+            assert_eq!(Pixel::RED.to_array(), vertices[0].fill_color);
+        } else {
+            panic!("expected draw command");
+        }
+    }
+
+    #[test]
+    fn polygon_outline_draws_each_ring() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+
+        let square = [[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0]];
+        let hole = [[4.0, 4.0], [6.0, 4.0], [6.0, 6.0]];
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_polygon_with_holes(&square, &[hole], Pixel::RED, 1.0);
+        let stats = renderer.end_scene(scene, &mut backend);
+
+        assert_eq!(2, stats.draw_calls);
+        assert_eq!(1, stats.pipeline_switches);
+    }
+
+    #[test]
+    fn gradient_is_evaluated_before_transform() {
+        let mut backend = RecordingBackend::new();
+        let renderer = Renderer::with_pixel_size(&backend, PhysicalSize::new(2, 2)).unwrap();
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.translate([100.0, 0.0]);
+        scene.draw_filled_rect(
+            [0.0, 0.0],
+            [10.0, 10.0],
+            Paint::linear_gradient([0.0, 0.0], [10.0, 0.0], Pixel::BLACK, Pixel::WHITE),
+        );
+        renderer.end_scene(scene, &mut backend);
+
+        if let RecordedCommand::Draw { vertices, .. } = &backend.frames()[0].commands[1] {
+            for vertex in vertices {
+                let expected = if vertex.coords[0] == 200.0 {
+                    Pixel::BLACK
+                } else {
+                    Pixel::WHITE
+                };
+                assert_eq!(expected.to_array(), vertex.fill_color);
+            }
+        } else {
+            panic!("expected draw command");
+        }
+    }
+
+    #[test]
+    fn rounded_rect_stays_inside_its_bounds() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_filled_rounded_rect([0.0, 0.0], [40.0, 20.0], [4.0; 4], Pixel::WHITE);
+        renderer.end_scene(scene, &mut backend);
+
+        if let RecordedCommand::Draw { vertices, .. } = &backend.frames()[0].commands[1] {
+            assert!(vertices.len() > 4);
+            assert!(!vertices.iter().any(|v| v.coords == [0.0, 0.0]));
+            assert!(vertices.iter().all(|v| {
+                (-1e-4..=40.0001).contains(&v.coords[0]) && (-1e-4..=20.0001).contains(&v.coords[1])
+            }));
+        } else {
+            panic!("expected draw command");
+        }
+    }
+
+    #[test]
+    fn mesh_is_transformed_and_drawn_with_its_texture() {
+        let mut backend = RecordingBackend::new();
+        let renderer = Renderer::with_pixel_size(&backend, PhysicalSize::new(2, 2)).unwrap();
+        let texture = Texture::new(2, 2, &backend, None);
+
+        let vertices = [
+            Vertex::new([0.0, 0.0], [0.0, 0.0], [1.0; 4]),
+            Vertex::new([10.0, 0.0], [1.0, 0.0], [1.0; 4]),
+            Vertex::new([0.0, 10.0], [0.0, 1.0], [1.0; 4]),
+        ];
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.translate([5.0, 0.0]);
+        scene.draw_mesh(
+            &vertices,
+            &[0, 1, 2],
+            Some(&texture),
+            Topology::TriangleList,
+        );
+        scene.draw_mesh(&vertices, &[0, 1, 3], None, Topology::TriangleList);
+        renderer.end_scene(scene, &mut backend);
+
+        let commands = &backend.frames()[0].commands;
+        assert_eq!(2, commands.len());
+
+        if let RecordedCommand::Draw {
+            texture: id,
+            vertices,
+            ..
+        } = &commands[1]
+        {
+            assert_eq!(RecordingBackend::texture_id(&texture), *id);
+            assert_eq!([30.0, 0.0], vertices[1].coords);
+            assert_eq!([1.0, 0.0], vertices[1].tex_coords);
+        } else {
+            panic!("expected draw command");
+        }
+    }
+
+    #[test]
+    fn only_strip_meshes_restart_and_lists_need_whole_triangles() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+
+        let vertices = [
+            Vertex::new([0.0, 0.0], [0.0, 0.0], [1.0; 4]),
+            Vertex::new([10.0, 0.0], [1.0, 0.0], [1.0; 4]),
+            Vertex::new([0.0, 10.0], [0.0, 1.0], [1.0; 4]),
+        ];
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_mesh(&vertices, &[0, 1, u32::MAX], None, Topology::TriangleList);
+        scene.draw_mesh(&vertices, &[0, 1, 2, 0], None, Topology::TriangleList);
+        scene.draw_mesh(
+            &vertices,
+            &[0, 1, 2, u32::MAX, 2, 1, 0],
+            None,
+            Topology::TriangleStrip,
+        );
+        renderer.end_scene(scene, &mut backend);
+
+        let commands = &backend.frames()[0].commands;
+        assert_eq!(2, commands.len());
+        assert!(matches!(
+            commands[1],
+            RecordedCommand::Draw {
+                topology: Topology::TriangleStrip,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn text_is_drawn_from_font_atlas() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+        let font = Font::from_bytes(
+            &backend,
+            include_bytes!("../examples/assets/FiraSans-Regular.ttf"),
+        )
+        .unwrap();
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_text(&font, "Hi", [10.0, 10.0], 16.0, Pixel::WHITE);
+        renderer.end_scene(scene, &mut backend);
+
+        if let RecordedCommand::Draw {
+            texture: id,
+            vertices,
+            ..
+        } = &backend.frames()[0].commands[1]
+        {
+            assert_eq!(RecordingBackend::texture_id(font.atlas()), *id);
+            assert_eq!(8, vertices.len());
+            assert!(vertices.iter().all(|v| v.coords[0] >= 10.0));
+        } else {
+            panic!("expected draw command");
+        }
+    }
+
+    #[test]
+    fn frames_keep_more_views_than_the_initial_view_buffer_holds() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        for i in 0..30 {
+            scene.set_view(View::new([i as f32, 0.0], 64.0, 64.0));
+            scene.draw_filled_rect([0.0, 0.0], [10.0, 10.0], Pixel::RED);
+        }
+        let stats = renderer.end_scene(scene, &mut backend);
+
+        let commands = &backend.frames()[0].commands;
+        let views = commands
+            .iter()
+            .filter(|command| matches!(command, RecordedCommand::View(_)))
+            .count();
+        assert_eq!(30, views);
+        assert_eq!(30, stats.draw_calls);
+    }
+
+    #[test]
+    fn views_are_scaled_by_pixel_size() {
+        let mut backend = RecordingBackend::new();
+        let renderer = Renderer::with_pixel_size(&backend, PhysicalSize::new(2, 4)).unwrap();
+
+        let viewport = Aabb::new(v2(0.5, 0.0), v2(1.0, 0.5));
+        let mut scene = Scene::new(View::new([160.0, 120.0], 320.0, 240.0));
+        scene.set_view(View::new([40.0, 30.0], 80.0, 60.0).with_viewport(viewport));
+        scene.draw_point([0.0, 0.0], Pixel::RED);
+        renderer.end_scene(scene, &mut backend);
+
+        if let RecordedCommand::View(view) = &backend.frames()[0].commands[0] {
+            assert_eq!(viewport, view.viewport());
+            assert_eq!(
+                View::new([80.0, 120.0], 160.0, 240.0).transform(),
+                view.transform()
+            );
+        } else {
+            panic!("expected view command");
+        }
+    }
+
+    #[test]
+    fn debug_text_is_drawn_last_in_screen_space() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+
+        let view = View::new([0.0, 0.0], 1280.0, 720.0);
+        let mut scene = Scene::new(view);
+        scene.debug_text("fps: 60");
+        scene.translate([100.0, 100.0]);
+        scene.debug_text("a\nb");
+        scene.draw_filled_rect([0.0, 0.0], [10.0, 10.0], Pixel::RED);
+        renderer.end_scene(scene, &mut backend);
+
+        let commands = &backend.frames()[0].commands;
+        assert_eq!(
+            RecordedCommand::View(View::from_origin(Origin::TopLeft, 1280.0, 720.0)),
+            commands[2]
+        );
+
+        let glyph_tops: Vec<f32> = commands[3..]
+            .iter()
+            .filter_map(|command| match command {
+                RecordedCommand::Draw {
+                    texture, vertices, ..
+                } if *texture != RecordingBackend::texture_id(&renderer.default_texture) => Some(
+                    vertices
+                        .iter()
+                        .map(|v| v.coords[1])
+                        .fold(f32::MAX, f32::min),
+                ),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![2.0, 12.0], glyph_tops);
+    }
+
+    #[test]
+    fn canvas_covers_the_window_whatever_the_view() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+        let canvas = Canvas::with_size(&backend, 320, 240);
+
+        let mut scene = Scene::new(View::from_origin(Origin::TopLeft, 320.0, 240.0));
+        let mut camera = View::new([500.0, 500.0], 320.0, 240.0);
+        camera.set_zoom(2.0);
+        scene.set_view(camera);
+        scene.translate([100.0, 100.0]);
+        scene.draw_canvas(&canvas);
+        scene.draw_filled_rect([0.0, 0.0], [10.0, 10.0], Pixel::RED);
+        renderer.end_scene(scene, &mut backend);
+
+        let commands = &backend.frames()[0].commands;
+        let RecordedCommand::Draw {
+            texture, vertices, ..
+        } = &commands[2]
+        else {
+            panic!("expected the canvas after the switch to the screen view");
+        };
+
+        assert_eq!(RecordingBackend::texture_id(canvas.texture()), *texture);
+        let xs = vertices.iter().map(|v| v.coords[0]);
+        let ys = vertices.iter().map(|v| v.coords[1]);
+        assert_eq!(
+            (0.0, 320.0, 0.0, 240.0),
+            (
+                xs.clone().fold(f32::MAX, f32::min),
+                xs.fold(f32::MIN, f32::max),
+                ys.clone().fold(f32::MAX, f32::min),
+                ys.fold(f32::MIN, f32::max)
+            )
+        );
+
+        let RecordedCommand::View(view) = commands[3] else {
+            panic!("expected the camera to be restored");
+        };
+        assert_eq!(2.0, view.zoom());
+    }
+
+    #[test]
+    fn anti_aliased_shapes_are_drawn_with_sdf_quads() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.set_anti_aliased(true);
+        scene.draw_filled_circle([50.0, 50.0], 10.0, Pixel::RED);
+        scene.draw_rect([0.0, 0.0], [10.0, 10.0], Pixel::RED, 2.0);
+        scene.draw_line([0.0, 0.0], [10.0, 10.0], Pixel::RED, 1.0);
+        scene.draw_filled_ellipse([50.0, 50.0], [10.0, 5.0], Pixel::RED);
+        scene.set_anti_aliased(false);
+        scene.draw_filled_circle([50.0, 50.0], 10.0, Pixel::RED);
+        let stats = renderer.end_scene(scene, &mut backend);
+
+        let commands = &backend.frames()[0].commands;
+        assert_eq!(6, commands.len());
+        assert!(commands[1..4]
+            .iter()
+            .all(|c| matches!(c, RecordedCommand::DrawSdf { .. })));
+        assert!(commands[4..]
+            .iter()
+            .all(|c| matches!(c, RecordedCommand::Draw { .. })));
+        assert_eq!(2, stats.pipeline_switches);
+
+        if let RecordedCommand::DrawSdf { vertices, indices } = &commands[1] {
+            assert_eq!(4, vertices.len());
+            assert_eq!(&vec![0, 1, 2, 0, 2, 3], indices);
+            assert_eq!([10.0; 4], vertices[0].radii);
+        }
+    }
+
+    #[test]
+    fn circle_segments_follow_pixel_size() {
+        let mut small_backend = RecordingBackend::new();
+        let small = Renderer::with_pixel_size(&small_backend, PhysicalSize::new(1, 1)).unwrap();
+        let mut large_backend = RecordingBackend::new();
+        let large = Renderer::with_pixel_size(&large_backend, PhysicalSize::new(8, 8)).unwrap();
+
+        let view = View::new([0.0, 0.0], 1280.0, 720.0);
+        let mut scene = Scene::new(view);
+        scene.draw_filled_circle([0.0, 0.0], 20.0, Pixel::RED);
+        let small_stats = small.end_scene(scene, &mut small_backend);
+
+        let mut scene = Scene::new(view);
+        scene.draw_filled_circle([0.0, 0.0], 20.0, Pixel::RED);
+        let large_stats = large.end_scene(scene, &mut large_backend);
+
+        assert!(large_stats.vertices > small_stats.vertices);
+    }
+
+    #[test]
+    fn stats_count_state_changes() {
+        let mut backend = RecordingBackend::new();
+        let renderer = renderer(&backend);
+        let texture = Texture::new(2, 2, &backend, None);
+
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_filled_rect([0.0, 0.0], [10.0, 10.0], Pixel::RED);
+        scene.draw_filled_rect([10.0, 0.0], [20.0, 10.0], Pixel::RED);
+        scene.draw_line([0.0, 0.0], [10.0, 10.0], Pixel::RED, 1.0);
+        scene.draw_textured_rect([0.0, 0.0], [10.0, 10.0], &texture);
+        let stats = renderer.end_scene(scene, &mut backend);
+
+        assert_eq!(4, stats.draw_calls);
+        assert_eq!(16, stats.vertices);
+        assert_eq!(22, stats.indices);
+        assert_eq!(3, stats.pipeline_switches);
+        assert_eq!(3, stats.bind_group_switches);
+        assert_eq!(1, stats.views);
+    }
 }
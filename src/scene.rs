@@ -1,13 +1,25 @@
 use mint::Vector2;
-use sgl_math::{v2, Vec2};
+use sgl_math::{v2, Transform, Vec2};
 
-use crate::{renderer::DrawCommand, Texture, View};
+use crate::{
+    debug_text::{DebugText, GLYPH_SIZE},
+    renderer::DrawCommand,
+    BitmapFont, Canvas, FillRule, Font, Paint, Path, PointStyle, Sprite, Stroke, TextLayout,
+    TextStyle, Texture, Topology, Vertex, View,
+};
 
 use super::pixel::Pixel;
 
 pub struct Scene<'scene> {
     pub(crate) clear_color: Option<Pixel>,
     pub(crate) draw_commands: Vec<DrawCommand<'scene>>,
+    transform: Transform,
+    transform_stack: Vec<Transform>,
+    anti_aliased: bool,
+    view: View,
+    pub(crate) screen_view: View,
+    pub(crate) debug_text: Vec<DebugText>,
+    debug_lines: usize,
 }
 
 impl<'scene> Scene<'scene> {
@@ -15,12 +27,121 @@ impl<'scene> Scene<'scene> {
         Self {
             clear_color: None,
             draw_commands: vec![DrawCommand::View(view)],
+            transform: Transform::IDENTITY,
+            transform_stack: Vec::new(),
+            anti_aliased: false,
+            view,
+            screen_view: view,
+            debug_text: Vec::new(),
+            debug_lines: 0,
         }
     }
 
     pub fn clear(&mut self, color: Pixel) {
         self.clear_color = Some(color);
         self.draw_commands.drain(1..);
+        self.debug_text.clear();
+        self.debug_lines = 0;
+
+        if self.view != self.screen_view {
+            self.draw_commands.push(DrawCommand::View(self.view));
+        }
+
+        if self.transform != Transform::IDENTITY {
+            self.draw_commands
+                .push(DrawCommand::Transform(self.transform));
+        }
+
+        if self.anti_aliased {
+            self.draw_commands.push(DrawCommand::AntiAliased(true));
+        }
+    }
+
+    pub fn view(&self) -> View {
+        self.view
+    }
+
+    /// Draw with `view` from now on, into its viewport. Switching views between draws lets
+    /// several cameras share a frame, as in split screen or a minimap.
+    pub fn set_view(&mut self, view: View) {
+        self.view = view;
+
+        if let Some(DrawCommand::View(last)) = self.draw_commands.last_mut() {
+            *last = view;
+        } else {
+            self.draw_commands.push(DrawCommand::View(view));
+        }
+    }
+
+    pub fn anti_aliased(&self) -> bool {
+        self.anti_aliased
+    }
+
+    /// Draw lines, rects and circles from now on with smooth edges, cut out by a signed distance
+    /// shader rather than built from triangles. Gradients, ellipses and other shapes are drawn
+    /// as before.
+    pub fn set_anti_aliased(&mut self, anti_aliased: bool) {
+        if self.anti_aliased == anti_aliased {
+            return;
+        }
+
+        self.anti_aliased = anti_aliased;
+
+        if let Some(DrawCommand::AntiAliased(last)) = self.draw_commands.last_mut() {
+            *last = anti_aliased;
+        } else {
+            self.draw_commands
+                .push(DrawCommand::AntiAliased(anti_aliased));
+        }
+    }
+
+    /// The transform applied to everything drawn from now on.
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Save the current transform and combine it with `transform`, so that subsequent drawing
+    /// is relative to it. Restore with [`Scene::pop_transform`].
+    pub fn push_transform(&mut self, transform: Transform) {
+        self.transform_stack.push(self.transform);
+        self.set_transform(self.transform * transform);
+    }
+
+    /// Restore the transform saved by the matching [`Scene::push_transform`].
+    pub fn pop_transform(&mut self) {
+        if let Some(transform) = self.transform_stack.pop() {
+            self.set_transform(transform);
+        }
+    }
+
+    pub fn translate<V>(&mut self, translation: V)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.set_transform(self.transform * Transform::translate(translation.into().into()));
+    }
+
+    /// Rotate by `angle` radians around the current origin.
+    pub fn rotate(&mut self, angle: f32) {
+        self.set_transform(self.transform * Transform::rotate(angle));
+    }
+
+    pub fn scale<V>(&mut self, scale: V)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.set_transform(self.transform * Transform::scale(scale.into().into()));
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+
+        // Consecutive changes without any drawing in between only need the latest transform.
+        if let Some(DrawCommand::Transform(last)) = self.draw_commands.last_mut() {
+            *last = transform;
+        } else {
+            self.draw_commands.push(DrawCommand::Transform(transform));
+        }
     }
 
     pub fn draw_point<V>(&mut self, point: V, color: Pixel)
@@ -32,6 +153,150 @@ impl<'scene> Scene<'scene> {
         self.draw_filled_rect(from, to, color);
     }
 
+    /// Draw `text` with its top-left corner at `position`. `size` is the height of the font in
+    /// scene units, and lines are split at `\n`.
+    pub fn draw_text<V>(
+        &mut self,
+        font: &'scene Font,
+        text: &str,
+        position: V,
+        size: f32,
+        color: Pixel,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        let position: Vec2 = position.into().into();
+        self.draw_text_box(font, text, position, position, TextStyle::new(size, color));
+    }
+
+    /// Draw `text` aligned inside the box from `from` to `to`, with the size, line height and
+    /// alignment of `style`. Text that doesn't fit spills out of the box.
+    pub fn draw_text_box<V>(
+        &mut self,
+        font: &'scene Font,
+        text: &str,
+        from: V,
+        to: V,
+        style: TextStyle,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::Text {
+            font,
+            text: text.to_string(),
+            from: from.into().into(),
+            to: to.into().into(),
+            style,
+        });
+    }
+
+    /// Draw `text` from a [`BitmapFont`] with its top-left corner at `position`. Text drawn at a
+    /// whole multiple of [`BitmapFont::size`] keeps the font's pixels crisp.
+    pub fn draw_bitmap_text<V>(
+        &mut self,
+        font: &'scene BitmapFont,
+        text: &str,
+        position: V,
+        size: f32,
+        color: Pixel,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        let position: Vec2 = position.into().into();
+        self.draw_bitmap_text_box(font, text, position, position, TextStyle::new(size, color));
+    }
+
+    /// Like [`Scene::draw_text_box`], with a [`BitmapFont`].
+    pub fn draw_bitmap_text_box<V>(
+        &mut self,
+        font: &'scene BitmapFont,
+        text: &str,
+        from: V,
+        to: V,
+        style: TextStyle,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::BitmapText {
+            font,
+            text: text.to_string(),
+            from: from.into().into(),
+            to: to.into().into(),
+            style,
+        });
+    }
+
+    /// Draw a [`TextLayout`] with its top-left corner at `position`.
+    pub fn draw_text_layout<V>(&mut self, layout: &'scene TextLayout<'scene>, position: V)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_text_layout_revealed(layout, position, layout.char_count());
+    }
+
+    /// Like [`Scene::draw_text_layout`], drawing only the first `visible` characters and icons
+    /// for a typewriter effect. Spaces count as characters.
+    pub fn draw_text_layout_revealed<V>(
+        &mut self,
+        layout: &'scene TextLayout<'scene>,
+        position: V,
+        visible: usize,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::TextLayout {
+            layout,
+            position: position.into().into(),
+            visible,
+        });
+    }
+
+    /// Write `text` in the top-left corner of the screen with the built-in debug font, below the
+    /// debug text written before it. Debug text is drawn over everything else in screen space,
+    /// whatever the view and transform.
+    pub fn debug_text(&mut self, text: &str) {
+        let position = v2(2.0, 2.0 + self.debug_lines as f32 * (GLYPH_SIZE + 2.0));
+        self.debug_lines += text.split('\n').count();
+
+        self.debug_text_at(text, position, Pixel::WHITE);
+    }
+
+    /// Like [`Scene::debug_text`], with the top-left corner of the text at `position` on the
+    /// screen.
+    pub fn debug_text_at<V>(&mut self, text: &str, position: V, color: Pixel)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.debug_text.push(DebugText {
+            text: text.to_string(),
+            position: position.into().into(),
+            color,
+        });
+    }
+
+    /// Draw many square points of `size` at once, each centered on its position.
+    pub fn draw_points<V>(&mut self, points: &[(V, Pixel)], size: f32)
+    where
+        V: Into<Vector2<f32>> + Copy,
+    {
+        self.draw_points_with_style(points, size, PointStyle::Square);
+    }
+
+    /// Like [`Scene::draw_points`], with the points drawn as squares or circles.
+    pub fn draw_points_with_style<V>(&mut self, points: &[(V, Pixel)], size: f32, style: PointStyle)
+    where
+        V: Into<Vector2<f32>> + Copy,
+    {
+        self.draw_commands.push(DrawCommand::Points {
+            points: points
+                .iter()
+                .map(|(position, color)| ((*position).into().into(), *color))
+                .collect(),
+            size,
+            style,
+        });
+    }
+
     pub fn draw_line<V>(&mut self, from: V, to: V, color: Pixel, thickness: f32)
     where
         V: Into<Vector2<f32>>,
@@ -51,19 +316,112 @@ impl<'scene> Scene<'scene> {
         self.draw_commands.push(DrawCommand::Rect {
             from: from.into().into(),
             to: to.into().into(),
+            radii: [0.0; 4],
             color,
             thickness,
         });
     }
 
-    pub fn draw_filled_rect<V>(&mut self, from: V, to: V, color: Pixel)
+    /// Draw a line with a [`Stroke`], which can be dashed. Like [`Scene::draw_line`], the
+    /// thickness is centered on the line.
+    pub fn draw_stroked_line<V>(&mut self, from: V, to: V, color: Pixel, stroke: Stroke)
     where
         V: Into<Vector2<f32>>,
     {
-        self.draw_commands.push(DrawCommand::RectFilled {
+        self.draw_commands.push(DrawCommand::Polyline {
+            points: vec![from.into().into(), to.into().into()],
+            closed: false,
+            color,
+            stroke,
+        });
+    }
+
+    /// Draw the outline of a rect with a [`Stroke`], which can be dashed. Like
+    /// [`Scene::draw_rect`], the thickness is outside the rect. The dashes start at `from` and
+    /// go around through `(to.x, from.y)`.
+    pub fn draw_stroked_rect<V>(&mut self, from: V, to: V, color: Pixel, stroke: Stroke)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        let from: Vec2 = from.into().into();
+        let to: Vec2 = to.into().into();
+
+        // Center the stroke on a rect grown by half of it, so it covers the same area as the
+        // outline of `draw_rect`.
+        let half = stroke.thickness / 2.0;
+        let grow = v2(
+            if to.x < from.x { -half } else { half },
+            if to.y < from.y { -half } else { half },
+        );
+        let (from, to) = (from - grow, to + grow);
+
+        self.draw_commands.push(DrawCommand::Polyline {
+            points: vec![from, v2(to.x, from.y), to, v2(from.x, to.y)],
+            closed: true,
+            color,
+            stroke,
+        });
+    }
+
+    /// Draw the outline of a rect with rounded corners. `radii` go clockwise from the top-left
+    /// corner, and are limited to half the shorter side.
+    pub fn draw_rounded_rect<V>(
+        &mut self,
+        from: V,
+        to: V,
+        radii: [f32; 4],
+        color: Pixel,
+        thickness: f32,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::Rect {
             from: from.into().into(),
             to: to.into().into(),
+            radii,
             color,
+            thickness,
+        });
+    }
+
+    /// Fill a rect with a color or a gradient [`Paint`].
+    pub fn draw_filled_rect<V, P>(&mut self, from: V, to: V, paint: P)
+    where
+        V: Into<Vector2<f32>>,
+        P: Into<Paint>,
+    {
+        self.draw_commands.push(DrawCommand::RectFilled {
+            from: from.into().into(),
+            to: to.into().into(),
+            radii: [0.0; 4],
+            paint: paint.into(),
+        })
+    }
+
+    /// Fill a rect with rounded corners, with `radii` like in [`Scene::draw_rounded_rect`].
+    pub fn draw_filled_rounded_rect<V, P>(&mut self, from: V, to: V, radii: [f32; 4], paint: P)
+    where
+        V: Into<Vector2<f32>>,
+        P: Into<Paint>,
+    {
+        self.draw_commands.push(DrawCommand::RectFilled {
+            from: from.into().into(),
+            to: to.into().into(),
+            radii,
+            paint: paint.into(),
+        })
+    }
+
+    /// Fill a rect with a color at each corner, blended across the rect. The corners are given
+    /// in order starting at `from`, then `(to.x, from.y)`, `to` and `(from.x, to.y)`.
+    pub fn draw_filled_rect_corners<V>(&mut self, from: V, to: V, colors: [Pixel; 4])
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::RectCorners {
+            from: from.into().into(),
+            to: to.into().into(),
+            colors,
         })
     }
 
@@ -96,11 +454,284 @@ impl<'scene> Scene<'scene> {
             sub_coords: Some((sub_coord_from.into().into(), sub_coord_to.into().into())),
         })
     }
+
+    /// Draw `canvas` over the whole window, whatever the current view and transform. Changes made
+    /// to it since it was last drawn are uploaded first.
+    pub fn draw_canvas(&mut self, canvas: &'scene Canvas) {
+        let view = self.view;
+        let screen = self.screen_view;
+        let (top, bottom) = (
+            screen.top().min(screen.bottom()),
+            screen.top().max(screen.bottom()),
+        );
+
+        if view != screen {
+            self.set_view(screen);
+        }
+
+        self.draw_commands.push(DrawCommand::Canvas {
+            from: v2(screen.left(), top),
+            to: v2(screen.right(), bottom),
+            canvas,
+        });
+
+        if view != screen {
+            self.set_view(view);
+        }
+    }
+
+    pub fn draw_circle<V>(&mut self, center: V, radius: f32, color: Pixel, thickness: f32)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::Ellipse {
+            center: center.into().into(),
+            radii: v2(radius, radius),
+            color,
+            thickness,
+        });
+    }
+
+    pub fn draw_filled_circle<V, P>(&mut self, center: V, radius: f32, paint: P)
+    where
+        V: Into<Vector2<f32>>,
+        P: Into<Paint>,
+    {
+        self.draw_commands.push(DrawCommand::EllipseFilled {
+            center: center.into().into(),
+            radii: v2(radius, radius),
+            paint: paint.into(),
+        });
+    }
+
+    pub fn draw_ellipse<V>(&mut self, center: V, radii: V, color: Pixel, thickness: f32)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::Ellipse {
+            center: center.into().into(),
+            radii: radii.into().into(),
+            color,
+            thickness,
+        });
+    }
+
+    pub fn draw_filled_ellipse<V, P>(&mut self, center: V, radii: V, paint: P)
+    where
+        V: Into<Vector2<f32>>,
+        P: Into<Paint>,
+    {
+        self.draw_commands.push(DrawCommand::EllipseFilled {
+            center: center.into().into(),
+            radii: radii.into().into(),
+            paint: paint.into(),
+        });
+    }
+
+    /// Draw part of a circle's outline. Angles are in radians, starting from the x-axis and
+    /// sweeping towards the y-axis.
+    pub fn draw_arc<V>(
+        &mut self,
+        center: V,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+        color: Pixel,
+        thickness: f32,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::Arc {
+            center: center.into().into(),
+            radii: v2(radius, radius),
+            start_angle,
+            sweep_angle,
+            color,
+            thickness,
+        });
+    }
+
+    /// Draw the outline of a pie slice. Angles are as for [`Scene::draw_arc`].
+    pub fn draw_sector<V>(
+        &mut self,
+        center: V,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+        color: Pixel,
+        thickness: f32,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::Sector {
+            center: center.into().into(),
+            radii: v2(radius, radius),
+            start_angle,
+            sweep_angle,
+            color,
+            thickness,
+        });
+    }
+
+    /// Draw a filled pie slice. Angles are as for [`Scene::draw_arc`].
+    pub fn draw_filled_sector<V>(
+        &mut self,
+        center: V,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+        color: Pixel,
+    ) where
+        V: Into<Vector2<f32>>,
+    {
+        self.draw_commands.push(DrawCommand::SectorFilled {
+            center: center.into().into(),
+            radii: v2(radius, radius),
+            start_angle,
+            sweep_angle,
+            color,
+        });
+    }
+
+    /// Draw connected line segments through `points`. The stroke can be given as just a
+    /// thickness.
+    pub fn draw_polyline<V, S>(&mut self, points: &[V], color: Pixel, stroke: S)
+    where
+        V: Into<Vector2<f32>> + Copy,
+        S: Into<Stroke>,
+    {
+        self.draw_commands.push(DrawCommand::Polyline {
+            points: to_points(points),
+            closed: false,
+            color,
+            stroke: stroke.into(),
+        });
+    }
+
+    /// Like [`Scene::draw_polyline`], with a segment from the last point back to the first.
+    pub fn draw_closed_polyline<V, S>(&mut self, points: &[V], color: Pixel, stroke: S)
+    where
+        V: Into<Vector2<f32>> + Copy,
+        S: Into<Stroke>,
+    {
+        self.draw_commands.push(DrawCommand::Polyline {
+            points: to_points(points),
+            closed: true,
+            color,
+            stroke: stroke.into(),
+        });
+    }
+
+    /// Draw the outline of a simple polygon, which may be concave.
+    pub fn draw_polygon<V>(&mut self, points: &[V], color: Pixel, thickness: f32)
+    where
+        V: Into<Vector2<f32>> + Copy,
+    {
+        self.draw_polygon_with_holes::<V, &[V]>(points, &[], color, thickness);
+    }
+
+    /// Draw a filled simple polygon, which may be concave.
+    pub fn draw_filled_polygon<V>(&mut self, points: &[V], color: Pixel)
+    where
+        V: Into<Vector2<f32>> + Copy,
+    {
+        self.draw_filled_polygon_with_holes::<V, &[V]>(points, &[], color);
+    }
+
+    /// Draw the outlines of a polygon and of the holes cut out of it.
+    pub fn draw_polygon_with_holes<V, H>(
+        &mut self,
+        points: &[V],
+        holes: &[H],
+        color: Pixel,
+        thickness: f32,
+    ) where
+        V: Into<Vector2<f32>> + Copy,
+        H: AsRef<[V]>,
+    {
+        self.draw_commands.push(DrawCommand::Polygon {
+            points: to_points(points),
+            holes: holes.iter().map(|hole| to_points(hole.as_ref())).collect(),
+            color,
+            thickness,
+        });
+    }
+
+    /// Draw a filled polygon with holes cut out of it. Holes must be inside the polygon and
+    /// must not overlap each other.
+    pub fn draw_filled_polygon_with_holes<V, H>(&mut self, points: &[V], holes: &[H], color: Pixel)
+    where
+        V: Into<Vector2<f32>> + Copy,
+        H: AsRef<[V]>,
+    {
+        self.draw_commands.push(DrawCommand::PolygonFilled {
+            points: to_points(points),
+            holes: holes.iter().map(|hole| to_points(hole.as_ref())).collect(),
+            color,
+        });
+    }
+
+    /// Fill the inside of `path`, with every sub-path treated as closed.
+    pub fn fill_path<P>(&mut self, path: &Path, paint: P, rule: FillRule)
+    where
+        P: Into<Paint>,
+    {
+        self.draw_commands.push(DrawCommand::PathFilled {
+            path: path.clone(),
+            paint: paint.into(),
+            rule,
+        });
+    }
+
+    /// Draw the lines and curves of `path`. The stroke can be given as just a thickness.
+    pub fn stroke_path<S>(&mut self, path: &Path, color: Pixel, stroke: S)
+    where
+        S: Into<Stroke>,
+    {
+        self.draw_commands.push(DrawCommand::Path {
+            path: path.clone(),
+            color,
+            stroke: stroke.into(),
+        });
+    }
+
+    /// Draw triangles made from `vertices`, in the order given by `indices`. The current
+    /// transform is applied to the vertex coords. Without a texture the triangles are filled with
+    /// their vertex colors.
+    ///
+    /// Meshes with indices out of range are skipped, as are lists not made of whole triangles.
+    /// Strips can restart with `u32::MAX`.
+    pub fn draw_mesh(
+        &mut self,
+        vertices: &[Vertex],
+        indices: &[u32],
+        texture: Option<&'scene Texture>,
+        topology: Topology,
+    ) {
+        self.draw_commands.push(DrawCommand::Mesh {
+            vertices: vertices.to_vec(),
+            indices: indices.to_vec(),
+            texture,
+            topology,
+        });
+    }
+
+    pub fn draw_sprite(&mut self, texture: &'scene Texture, sprite: Sprite) {
+        self.draw_commands
+            .push(DrawCommand::Sprite { texture, sprite })
+    }
+}
+
+fn to_points<V>(points: &[V]) -> Vec<Vec2>
+where
+    V: Into<Vector2<f32>> + Copy,
+{
+    points.iter().map(|point| (*point).into().into()).collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use sgl_math::v2;
+    use sgl_math::{v2, Aabb};
 
     use crate::View;
 
@@ -119,12 +750,57 @@ mod tests {
         let scene = Scene::new(window_view);
 
         if let DrawCommand::View(actual_view) =
-            scene.draw_commands.get(0).expect("draw_command::view")
+            scene.draw_commands.first().expect("draw_command::view")
         {
             assert_eq!(window_view, *actual_view);
         };
     }
 
+    #[test]
+    fn set_view_switches_view_between_draws() {
+        let window_view = View::new([0.0, 0.0], 1280.0, 720.0);
+        let left = View::new([0.0, 0.0], 640.0, 720.0)
+            .with_viewport(Aabb::new(v2(0.0, 0.0), v2(0.5, 1.0)));
+        let right = View::new([100.0, 0.0], 640.0, 720.0)
+            .with_viewport(Aabb::new(v2(0.5, 0.0), v2(1.0, 1.0)));
+
+        let mut scene = Scene::new(window_view);
+        scene.set_view(left);
+        scene.draw_point([0.0, 0.0], Pixel::RED);
+        scene.set_view(window_view);
+        scene.set_view(right);
+        scene.draw_point([0.0, 0.0], Pixel::RED);
+
+        let views: Vec<View> = scene
+            .draw_commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::View(view) => Some(*view),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![left, right], views);
+        assert_eq!(right, scene.view());
+
+        scene.clear(Pixel::BLACK);
+        assert!(matches!(scene.draw_commands[1], DrawCommand::View(view) if view == right));
+    }
+
+    #[test]
+    fn debug_text_lines_stack_until_cleared() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.debug_text("a\nb");
+        scene.debug_text("c");
+
+        let positions: Vec<Vec2> = scene.debug_text.iter().map(|text| text.position).collect();
+        assert_eq!(vec![v2(2.0, 2.0), v2(2.0, 22.0)], positions);
+
+        scene.clear(Pixel::BLACK);
+        scene.debug_text("d");
+        assert_eq!(v2(2.0, 2.0), scene.debug_text[0].position);
+        assert_eq!(1, scene.debug_text.len());
+    }
+
     #[test]
     fn scene_draw_line() {
         let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
@@ -164,6 +840,7 @@ mod tests {
             to,
             color,
             thickness,
+            ..
         } = scene.draw_commands.get(1).expect("draw_command::rect")
         {
             assert_eq!(rect_from, *from);
@@ -182,14 +859,218 @@ mod tests {
         let rect_color = Pixel::WHITE;
         scene.draw_filled_rect(rect_from, rect_to, rect_color);
 
-        if let DrawCommand::RectFilled { from, to, color } = scene
+        if let DrawCommand::RectFilled {
+            from, to, paint, ..
+        } = scene
             .draw_commands
             .get(1)
             .expect("draw_command::rect_filled")
         {
             assert_eq!(rect_from, *from);
             assert_eq!(rect_to, *to);
-            assert_eq!(rect_color, *color);
+            assert_eq!(Paint::Solid(rect_color), *paint);
+        };
+    }
+
+    #[test]
+    fn scene_draw_circle() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        scene.draw_circle(v2(10.0, 20.0), 5.0, Pixel::WHITE, 2.0);
+
+        if let DrawCommand::Ellipse {
+            center,
+            radii,
+            color,
+            thickness,
+        } = scene.draw_commands.get(1).expect("draw_command::ellipse")
+        {
+            assert_eq!(v2(10.0, 20.0), *center);
+            assert_eq!(v2(5.0, 5.0), *radii);
+            assert_eq!(Pixel::WHITE, *color);
+            assert_eq!(2.0, *thickness);
+        };
+    }
+
+    #[test]
+    fn scene_draw_filled_ellipse() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        scene.draw_filled_ellipse(v2(10.0, 20.0), v2(5.0, 8.0), Pixel::RED);
+
+        if let DrawCommand::EllipseFilled {
+            center,
+            radii,
+            paint,
+        } = scene
+            .draw_commands
+            .get(1)
+            .expect("draw_command::ellipse_filled")
+        {
+            assert_eq!(v2(10.0, 20.0), *center);
+            assert_eq!(v2(5.0, 8.0), *radii);
+            assert_eq!(Paint::Solid(Pixel::RED), *paint);
+        };
+    }
+
+    #[test]
+    fn scene_draw_filled_circle_with_gradient() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        let gradient = Paint::radial_gradient([10.0, 20.0], 5.0, Pixel::WHITE, Pixel::RED);
+        scene.draw_filled_circle(v2(10.0, 20.0), 5.0, gradient.clone());
+
+        if let DrawCommand::EllipseFilled { paint, .. } = scene
+            .draw_commands
+            .get(1)
+            .expect("draw_command::ellipse_filled")
+        {
+            assert_eq!(gradient, *paint);
+        };
+    }
+
+    #[test]
+    fn scene_draw_arc() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        scene.draw_arc(v2(10.0, 20.0), 5.0, 0.5, 1.5, Pixel::WHITE, 2.0);
+
+        if let DrawCommand::Arc {
+            start_angle,
+            sweep_angle,
+            thickness,
+            ..
+        } = scene.draw_commands.get(1).expect("draw_command::arc")
+        {
+            assert_eq!(0.5, *start_angle);
+            assert_eq!(1.5, *sweep_angle);
+            assert_eq!(2.0, *thickness);
+        };
+    }
+
+    #[test]
+    fn scene_draw_filled_sector() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        scene.draw_filled_sector(v2(10.0, 20.0), 5.0, 0.5, 1.5, Pixel::WHITE);
+
+        if let DrawCommand::SectorFilled {
+            radii,
+            start_angle,
+            sweep_angle,
+            ..
+        } = scene
+            .draw_commands
+            .get(1)
+            .expect("draw_command::sector_filled")
+        {
+            assert_eq!(v2(5.0, 5.0), *radii);
+            assert_eq!(0.5, *start_angle);
+            assert_eq!(1.5, *sweep_angle);
+        };
+    }
+
+    #[test]
+    fn scene_draw_polyline() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        scene.draw_polyline(&[[0.0, 0.0], [10.0, 0.0]], Pixel::WHITE, 2.0);
+
+        if let DrawCommand::Polyline {
+            points,
+            closed,
+            stroke,
+            ..
+        } = scene.draw_commands.get(1).expect("draw_command::polyline")
+        {
+            assert_eq!(&vec![v2(0.0, 0.0), v2(10.0, 0.0)], points);
+            assert!(!closed);
+            assert_eq!(Stroke::new(2.0), *stroke);
+        };
+    }
+
+    #[test]
+    fn scene_draw_filled_polygon_with_holes() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        let square = [[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0]];
+        let hole = [[4.0, 4.0], [6.0, 4.0], [6.0, 6.0]];
+        scene.draw_filled_polygon_with_holes(&square, &[hole], Pixel::RED);
+
+        if let DrawCommand::PolygonFilled {
+            points,
+            holes,
+            color,
+        } = scene
+            .draw_commands
+            .get(1)
+            .expect("draw_command::polygon_filled")
+        {
+            assert_eq!(4, points.len());
+            assert_eq!(&vec![vec![v2(4.0, 4.0), v2(6.0, 4.0), v2(6.0, 6.0)]], holes);
+            assert_eq!(Pixel::RED, *color);
+        };
+    }
+
+    #[test]
+    fn scene_fill_path() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        let path = Path::new().move_to([0.0, 0.0]).line_to([10.0, 0.0]).close();
+        scene.fill_path(&path, Pixel::RED, FillRule::EvenOdd);
+
+        if let DrawCommand::PathFilled {
+            path: actual_path,
+            paint,
+            rule,
+        } = scene
+            .draw_commands
+            .get(1)
+            .expect("draw_command::path_filled")
+        {
+            assert_eq!(path, *actual_path);
+            assert_eq!(Paint::Solid(Pixel::RED), *paint);
+            assert_eq!(FillRule::EvenOdd, *rule);
+        };
+    }
+
+    #[test]
+    fn scene_draw_mesh() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        let vertices = [Vertex::new([0.0, 0.0], [0.0, 0.0], Pixel::RED.to_array()); 3];
+        scene.draw_mesh(&vertices, &[0, 1, 2], None, Topology::TriangleStrip);
+
+        if let DrawCommand::Mesh {
+            vertices: actual_vertices,
+            indices,
+            texture,
+            topology,
+        } = scene.draw_commands.get(1).expect("draw_command::mesh")
+        {
+            assert_eq!(&vertices.to_vec(), actual_vertices);
+            assert_eq!(&vec![0, 1, 2], indices);
+            assert!(texture.is_none());
+            assert_eq!(Topology::TriangleStrip, *topology);
+        };
+    }
+
+    #[test]
+    fn scene_draw_sprite() {
+        let backend = crate::RecordingBackend::new();
+        let texture = Texture::new(4, 4, &backend, None);
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+
+        let sprite = Sprite::new(v2(10.0, 20.0)).with_rotation(1.0);
+        scene.draw_sprite(&texture, sprite);
+
+        if let DrawCommand::Sprite {
+            texture: actual_texture,
+            sprite: actual_sprite,
+        } = scene.draw_commands.get(1).expect("draw_command::sprite")
+        {
+            assert!(std::ptr::eq(&texture, *actual_texture));
+            assert_eq!(sprite, *actual_sprite);
         };
     }
 
@@ -201,17 +1082,136 @@ mod tests {
         let point_color = Pixel::WHITE;
         scene.draw_point(point_from, point_color);
 
-        if let DrawCommand::RectFilled { from, to, color } = scene
+        if let DrawCommand::RectFilled {
+            from, to, paint, ..
+        } = scene
             .draw_commands
             .get(1)
             .expect("draw_command::rect_filled")
         {
             assert_eq!(point_from, *from);
             assert_eq!(point_from + v2(1.0, 1.0), *to);
-            assert_eq!(point_color, *color);
+            assert_eq!(Paint::Solid(point_color), *paint);
         };
     }
 
+    #[test]
+    fn scene_draw_points_is_one_command() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_points(&[([0.0, 0.0], Pixel::RED), ([5.0, 5.0], Pixel::GREEN)], 2.0);
+
+        assert_eq!(2, scene.draw_commands.len());
+        if let DrawCommand::Points {
+            points,
+            size,
+            style,
+        } = &scene.draw_commands[1]
+        {
+            assert_eq!(
+                &vec![(v2(0.0, 0.0), Pixel::RED), (v2(5.0, 5.0), Pixel::GREEN)],
+                points
+            );
+            assert_eq!(2.0, *size);
+            assert_eq!(PointStyle::Square, *style);
+        } else {
+            panic!("expected points");
+        }
+    }
+
+    #[test]
+    fn push_transform_combines_with_current_transform() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.push_transform(Transform::translate(v2(10.0, 0.0)));
+        scene.push_transform(Transform::scale(v2(2.0, 2.0)));
+
+        let expected = Transform::translate(v2(10.0, 0.0)) * Transform::scale(v2(2.0, 2.0));
+        assert_eq!(expected, scene.transform());
+
+        if let DrawCommand::Transform(transform) =
+            scene.draw_commands.get(1).expect("draw_command::transform")
+        {
+            assert_eq!(expected, *transform);
+        };
+    }
+
+    #[test]
+    fn pop_transform_restores_previous_transform() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.translate(v2(10.0, 0.0));
+        scene.draw_point(v2(0.0, 0.0), Pixel::WHITE);
+        scene.push_transform(Transform::rotate(1.0));
+        scene.draw_point(v2(0.0, 0.0), Pixel::WHITE);
+        scene.pop_transform();
+
+        assert_eq!(Transform::translate(v2(10.0, 0.0)), scene.transform());
+
+        if let DrawCommand::Transform(transform) =
+            scene.draw_commands.last().expect("draw_command::transform")
+        {
+            assert_eq!(Transform::translate(v2(10.0, 0.0)), *transform);
+        };
+    }
+
+    #[test]
+    fn pop_transform_without_push_keeps_identity() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.pop_transform();
+
+        assert_eq!(Transform::IDENTITY, scene.transform());
+        assert_eq!(1, scene.draw_commands.len());
+    }
+
+    #[test]
+    fn clear_scene_keeps_current_transform() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.translate(v2(10.0, 0.0));
+        scene.draw_point(v2(0.0, 0.0), Pixel::WHITE);
+        scene.clear(Pixel::BLACK);
+
+        assert_eq!(2, scene.draw_commands.len());
+
+        if let DrawCommand::Transform(transform) =
+            scene.draw_commands.get(1).expect("draw_command::transform")
+        {
+            assert_eq!(Transform::translate(v2(10.0, 0.0)), *transform);
+        };
+    }
+
+    #[test]
+    fn stroked_rect_is_grown_by_half_the_thickness() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.draw_stroked_rect(
+            [10.0, 10.0],
+            [0.0, 20.0],
+            Pixel::WHITE,
+            Stroke::new(2.0).with_dashes(&[4.0]),
+        );
+
+        if let DrawCommand::Polyline { points, closed, .. } = &scene.draw_commands[1] {
+            assert!(closed);
+            assert_eq!(
+                &vec![v2(11.0, 9.0), v2(-1.0, 9.0), v2(-1.0, 21.0), v2(11.0, 21.0)],
+                points
+            );
+        } else {
+            panic!("expected polyline");
+        }
+    }
+
+    #[test]
+    fn clear_scene_keeps_anti_aliasing() {
+        let mut scene = Scene::new(View::new([0.0, 0.0], 1280.0, 720.0));
+        scene.set_anti_aliased(true);
+        scene.draw_point(v2(0.0, 0.0), Pixel::WHITE);
+        scene.clear(Pixel::BLACK);
+
+        assert!(scene.anti_aliased());
+        assert!(matches!(
+            scene.draw_commands.get(1),
+            Some(DrawCommand::AntiAliased(true))
+        ));
+    }
+
     #[test]
     fn clear_scene_removes_draw_commands() {
         let window_view = View::new([0.0, 0.0], 1280.0, 720.0);
@@ -222,7 +1222,7 @@ mod tests {
         assert_eq!(Some(Pixel::YELLOW), scene.clear_color);
 
         if let DrawCommand::View(actual_view) =
-            scene.draw_commands.get(0).expect("draw_command::view")
+            scene.draw_commands.first().expect("draw_command::view")
         {
             assert_eq!(window_view, *actual_view);
         };
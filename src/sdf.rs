@@ -0,0 +1,240 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+use sgl_math::{v2, Transform, Vec2};
+use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
+
+use crate::Pixel;
+
+/// A corner of a quad whose shape is cut out by the signed distance fragment shader.
+///
+/// `local` is the position relative to the center of the shape, in the shape's own units, and
+/// `radii` are the corner radii from the top-left corner clockwise. Everything within `border`
+/// outside the edge is drawn in `border_color`.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SdfVertex {
+    pub coords: [f32; 2],
+    pub local: [f32; 2],
+    pub half_size: [f32; 2],
+    pub radii: [f32; 4],
+    pub border: f32,
+    pub fill_color: [f32; 4],
+    pub border_color: [f32; 4],
+}
+
+unsafe impl Pod for SdfVertex {}
+unsafe impl Zeroable for SdfVertex {}
+
+impl SdfVertex {
+    const ATTRIBUTES: [VertexAttribute; 7] = [
+        // Coords.
+        VertexAttribute {
+            offset: 0,
+            format: VertexFormat::Float32x2,
+            shader_location: 0,
+        },
+        // Local.
+        VertexAttribute {
+            offset: 8,
+            format: VertexFormat::Float32x2,
+            shader_location: 1,
+        },
+        // Half size.
+        VertexAttribute {
+            offset: 16,
+            format: VertexFormat::Float32x2,
+            shader_location: 2,
+        },
+        // Radii.
+        VertexAttribute {
+            offset: 24,
+            format: VertexFormat::Float32x4,
+            shader_location: 3,
+        },
+        // Border.
+        VertexAttribute {
+            offset: 40,
+            format: VertexFormat::Float32,
+            shader_location: 4,
+        },
+        // Fill color.
+        VertexAttribute {
+            offset: 44,
+            format: VertexFormat::Float32x4,
+            shader_location: 5,
+        },
+        // Border color.
+        VertexAttribute {
+            offset: 60,
+            format: VertexFormat::Float32x4,
+            shader_location: 6,
+        },
+    ];
+
+    pub(crate) fn desc<'a>() -> VertexBufferLayout<'a> {
+        VertexBufferLayout {
+            array_stride: size_of::<SdfVertex>() as BufferAddress,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// A box with rounded corners, centered on the origin of `frame`.
+pub(crate) struct SdfShape {
+    frame: Transform,
+    half_size: Vec2,
+    radii: [f32; 4],
+}
+
+impl SdfShape {
+    /// Extra space around the shape, in physical pixels, for the anti-aliased edge.
+    const MARGIN: f32 = 1.5;
+
+    pub(crate) fn rect(from: Vec2, to: Vec2, radii: [f32; 4]) -> Self {
+        let half_size = v2((to.x - from.x).abs(), (to.y - from.y).abs()) / 2.0;
+        let max_radius = f32::min(half_size.x, half_size.y);
+
+        Self {
+            frame: Transform::translate((from + to) / 2.0),
+            half_size,
+            radii: radii.map(|radius| radius.clamp(0.0, max_radius)),
+        }
+    }
+
+    pub(crate) fn circle(center: Vec2, radius: f32) -> Self {
+        let radius = radius.abs();
+
+        Self {
+            frame: Transform::translate(center),
+            half_size: v2(radius, radius),
+            radii: [radius; 4],
+        }
+    }
+
+    /// A line with square ends that finish at `from` and `to`.
+    pub(crate) fn line(from: Vec2, to: Vec2, thickness: f32) -> Self {
+        let direction = to - from;
+
+        Self {
+            frame: Transform::translate((from + to) / 2.0)
+                * Transform::rotate(direction.y.atan2(direction.x)),
+            half_size: v2(direction.len() / 2.0, thickness.abs() / 2.0),
+            radii: [0.0; 4],
+        }
+    }
+
+    pub(crate) fn fill_geometry(
+        &self,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<SdfVertex>, Vec<u32>) {
+        self.geometry(0.0, color, Pixel::rgba(0, 0, 0, 0), transform)
+    }
+
+    /// An outline of `thickness` outside the edge of the shape.
+    pub(crate) fn outline_geometry(
+        &self,
+        thickness: f32,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<SdfVertex>, Vec<u32>) {
+        let transparent = Pixel::rgba(color.r, color.g, color.b, 0);
+
+        self.geometry(thickness, transparent, color, transform)
+    }
+
+    fn geometry(
+        &self,
+        border: f32,
+        fill_color: Pixel,
+        border_color: Pixel,
+        transform: Transform,
+    ) -> (Vec<SdfVertex>, Vec<u32>) {
+        let transform = transform * self.frame;
+
+        // Enough room for the edge to fade out over a pixel or so on the most squashed axis.
+        let scale = f32::min(transform.x_axis.len(), transform.y_axis.len());
+        if scale <= 0.0 {
+            return (Vec::new(), Vec::new());
+        }
+
+        let margin = border + Self::MARGIN / scale;
+        let extent = self.half_size + v2(margin, margin);
+
+        let vertices = [
+            v2(-extent.x, -extent.y),
+            v2(extent.x, -extent.y),
+            v2(extent.x, extent.y),
+            v2(-extent.x, extent.y),
+        ]
+        .map(|local| SdfVertex {
+            coords: transform.transform_point(local).to_array(),
+            local: local.to_array(),
+            half_size: self.half_size.to_array(),
+            radii: self.radii,
+            border,
+            fill_color: fill_color.to_array(),
+            border_color: border_color.to_array(),
+        })
+        .to_vec();
+
+        (vertices, vec![0, 1, 2, 0, 2, 3])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sdf_vertex_attributes_match_layout() {
+        let last = SdfVertex::ATTRIBUTES[SdfVertex::ATTRIBUTES.len() - 1];
+
+        assert_eq!(
+            size_of::<SdfVertex>() as BufferAddress,
+            last.offset + last.format.size()
+        );
+    }
+
+    #[test]
+    fn rect_quad_covers_shape_and_margin() {
+        let rect = SdfShape::rect(v2(10.0, 10.0), v2(30.0, 20.0), [0.0; 4]);
+        let (vertices, indices) = rect.fill_geometry(Pixel::RED, Transform::IDENTITY);
+
+        assert_eq!(vec![0, 1, 2, 0, 2, 3], indices);
+        assert_eq!([8.5, 8.5], vertices[0].coords);
+        assert_eq!([31.5, 21.5], vertices[2].coords);
+        assert_eq!([-11.5, -6.5], vertices[0].local);
+        assert_eq!([10.0, 5.0], vertices[0].half_size);
+    }
+
+    #[test]
+    fn corner_radii_are_clamped_to_half_size() {
+        let rect = SdfShape::rect(v2(0.0, 0.0), v2(20.0, 10.0), [2.0, 8.0, -1.0, 5.0]);
+
+        assert_eq!([2.0, 5.0, 0.0, 5.0], rect.radii);
+    }
+
+    #[test]
+    fn outline_leaves_inside_transparent() {
+        let circle = SdfShape::circle(v2(0.0, 0.0), 10.0);
+        let (vertices, _) =
+            circle.outline_geometry(2.0, Pixel::RED, Transform::scale(v2(2.0, 2.0)));
+
+        assert_eq!(0.0, vertices[0].fill_color[3]);
+        assert_eq!(Pixel::RED.to_array(), vertices[0].border_color);
+        assert_eq!([-24.0 - 1.5, -24.0 - 1.5], vertices[0].coords);
+    }
+
+    #[test]
+    fn line_is_rotated_into_place() {
+        let line = SdfShape::line(v2(0.0, 0.0), v2(0.0, 10.0), 2.0);
+        let (vertices, _) = line.fill_geometry(Pixel::RED, Transform::IDENTITY);
+
+        assert_eq!([5.0, 1.0], vertices[0].half_size);
+        let corner = vertices[0].coords;
+        assert!((corner[0] - 2.5).abs() < 1e-4 && (corner[1] + 1.5).abs() < 1e-4);
+    }
+}
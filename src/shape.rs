@@ -1,6 +1,12 @@
-use sgl_math::{v2, Vec2};
+use std::f32::consts::{PI, TAU};
 
-use crate::{geometry::Vertex, Pixel, Texture};
+use sgl_math::{v2, Transform, Vec2};
+
+use crate::{
+    geometry::Vertex,
+    triangulate::{fill_trapezoids, triangulate},
+    FillRule, LineCap, LineJoin, Path, Pixel, Stroke, Texture,
+};
 
 pub(crate) struct LineShape {
     from: Vec2,
@@ -16,31 +22,30 @@ impl LineShape {
         &self,
         thickness: f32,
         color: Pixel,
-        pixel_size: Vec2,
+        transform: Transform,
     ) -> (Vec<Vertex>, Vec<u32>) {
         let fill_color = color.to_array();
 
-        let extent = (self.to - self.from).perp_cw().norm() * thickness;
+        let extent = (self.to - self.from).perp_cw().norm() * thickness / 2.0;
 
         let vertices = vec![
             Vertex {
-                coords: (v2(self.from.x, self.from.y) * pixel_size).to_array(),
+                coords: transform.transform_point(self.from - extent).to_array(),
                 tex_coords: [0.0, 1.0],
                 fill_color,
             },
             Vertex {
-                coords: (v2(self.to.x, self.to.y) * pixel_size).to_array(),
+                coords: transform.transform_point(self.to - extent).to_array(),
                 tex_coords: [0.0, 0.0],
                 fill_color,
             },
             Vertex {
-                coords: (v2(self.from.x + extent.x, self.from.y + extent.y) * pixel_size)
-                    .to_array(),
+                coords: transform.transform_point(self.from + extent).to_array(),
                 tex_coords: [1.0, 1.0],
                 fill_color,
             },
             Vertex {
-                coords: (v2(self.to.x + extent.x, self.to.y + extent.y) * pixel_size).to_array(),
+                coords: transform.transform_point(self.to + extent).to_array(),
                 tex_coords: [1.0, 0.0],
                 fill_color,
             },
@@ -56,6 +61,7 @@ pub(crate) struct RectangleShape {
     from: Vec2,
     to: Vec2,
     point_count: usize,
+    radii: [f32; 4],
 }
 
 impl RectangleShape {
@@ -64,6 +70,18 @@ impl RectangleShape {
             from,
             to,
             point_count: 4,
+            radii: [0.0; 4],
+        }
+    }
+
+    /// A rectangle with its corners rounded off, with radii from the top-left corner clockwise.
+    /// Radii are limited to half the shorter side.
+    pub(crate) fn rounded(from: Vec2, to: Vec2, radii: [f32; 4]) -> Self {
+        let max_radius = f32::min((to.x - from.x).abs() / 2.0, (to.y - from.y).abs() / 2.0);
+
+        Self {
+            radii: radii.map(|radius| radius.clamp(0.0, max_radius)),
+            ..Self::new(from, to)
         }
     }
 
@@ -77,6 +95,57 @@ impl RectangleShape {
         }
     }
 
+    fn is_rounded(&self) -> bool {
+        self.radii.iter().any(|radius| *radius > 0.0)
+    }
+
+    /// The outline, going down the left side first like [`RectangleShape::point`], with arcs
+    /// fine enough for the corners' size on screen.
+    fn points(&self, transform: Transform) -> Vec<Vec2> {
+        if !self.is_rounded() {
+            return (0..self.point_count).map(|i| self.point(i)).collect();
+        }
+
+        let min = v2(self.from.x.min(self.to.x), self.from.y.min(self.to.y));
+        let max = v2(self.from.x.max(self.to.x), self.from.y.max(self.to.y));
+        let [top_left, top_right, bottom_right, bottom_left] = self.radii;
+
+        let corners = [
+            (v2(min.x, min.y), v2(1.0, 1.0), top_left, PI),
+            (v2(min.x, max.y), v2(1.0, -1.0), bottom_left, PI / 2.0),
+            (v2(max.x, max.y), v2(-1.0, -1.0), bottom_right, 0.0),
+            (v2(max.x, min.y), v2(-1.0, 1.0), top_right, 1.5 * PI),
+        ];
+
+        // Corners rounded by half a side meet in one point, which must only be there once for
+        // outlines to find the direction of their edges.
+        let epsilon = 1e-4 * f32::max(max.x - min.x, max.y - min.y);
+        let same = |a: Vec2, b: Vec2| (a - b).len() <= epsilon;
+
+        let mut points: Vec<Vec2> = Vec::new();
+        for (corner, inward, radius, start_angle) in corners {
+            let corner_points = if radius > 0.0 {
+                let center = corner + inward * radius;
+                let arc = EllipseShape::arc(center, v2(radius, radius), start_angle, PI / 2.0);
+                arc.points(transform)
+            } else {
+                vec![corner]
+            };
+
+            for point in corner_points {
+                if !points.last().is_some_and(|last| same(*last, point)) {
+                    points.push(point);
+                }
+            }
+        }
+
+        if points.len() > 1 && same(points[0], points[points.len() - 1]) {
+            points.pop();
+        }
+
+        points
+    }
+
     fn width(&self) -> f32 {
         self.to.x - self.from.x
     }
@@ -89,86 +158,102 @@ impl RectangleShape {
         &self,
         thickness: f32,
         color: Pixel,
-        pixel_size: Vec2,
+        transform: Transform,
     ) -> (Vec<Vertex>, Vec<u32>) {
-        let mut vertices: Vec<Vertex> = Vec::with_capacity((self.point_count + 1) * 2);
-        let mut indices = Vec::with_capacity((self.point_count + 1) * 2);
-
-        for i in 0..self.point_count {
-            // https://stackoverflow.com/questions/69631855/extrude-2d-vertices-vectors
-
-            // Get the normals of the vectors either side of the current point (the in and out vectors).
-            let idx_p1 = (i + 1) % self.point_count;
-            let idx_p2 = if i == 0 { self.point_count - 1 } else { i - 1 };
-
-            let p0 = self.point(i);
-            let p1 = self.point(idx_p1);
-            let p2 = self.point(idx_p2);
-
-            let v_in = (p0 - p1).perp_cw().norm();
-            let v_out = (p2 - p0).perp_cw().norm();
+        let points = self.points(transform);
 
-            // Bisect the normals.
-            let mut bisector = v_in + v_out;
-            bisector /= bisector.dot(v_in).abs();
-            bisector *= thickness;
+        outline_geometry(&points, true, thickness, color, transform)
+    }
 
-            // Add the original vertex and the extruded vertex to the geometry.
-            vertices.push(Vertex::new(
-                (p0 * pixel_size).to_array(),
-                [0.0, 0.0],
-                color.to_array(),
-            ));
-            vertices.push(Vertex::new(
-                ((p0 + bisector) * pixel_size).to_array(),
-                [0.0, 0.0],
-                color.to_array(),
-            ));
+    pub(crate) fn fill_geometry(
+        &self,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let fill_color = color.to_array();
+        let points = self.points(transform);
 
-            // And the indices for each.
-            indices.push(i as u32 * 2);
-            indices.push(i as u32 * 2 + 1);
-        }
+        let vertices: Vec<Vertex> = points
+            .iter()
+            .map(|point| {
+                Vertex::new(
+                    transform.transform_point(*point).to_array(),
+                    [0.0, 0.0],
+                    fill_color,
+                )
+            })
+            .collect();
 
-        // Close the outline.
-        indices.push(indices[0]);
-        indices.push(indices[1]);
+        let indices = if self.is_rounded() {
+            // The shape is convex, so a fan from any point on it covers it.
+            (1..vertices.len() as u32 - 1)
+                .flat_map(|i| [0, i, i + 1])
+                .collect()
+        } else {
+            vec![0, 1, 3, 3, 1, 2]
+        };
 
         (vertices, indices)
     }
 
-    pub(crate) fn fill_geometry(&self, color: Pixel, pixel_size: Vec2) -> (Vec<Vertex>, Vec<u32>) {
-        let fill_color = color.to_array();
-
-        let mut vertices = Vec::with_capacity(self.point_count);
+    /// A fan around the center, which gets the average of the corner colors, so the blend is
+    /// the same along both diagonals.
+    pub(crate) fn corner_fill_geometry(
+        &self,
+        colors: [Pixel; 4],
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let corners = [
+            self.from,
+            v2(self.to.x, self.from.y),
+            self.to,
+            v2(self.from.x, self.to.y),
+        ];
 
-        for i in 0..self.point_count {
-            let coords = self.point(i as usize);
+        let colors = colors.map(|color| color.to_array());
+        let center_color = [0, 1, 2, 3].map(|i| colors.iter().map(|c| c[i]).sum::<f32>() / 4.0);
 
-            vertices.push(Vertex {
-                coords: (coords * pixel_size).to_array(),
-                tex_coords: [0.0, 0.0],
-                fill_color,
-            });
-        }
+        let mut vertices = vec![Vertex::new(
+            transform
+                .transform_point((self.from + self.to) / 2.0)
+                .to_array(),
+            [0.0, 0.0],
+            center_color,
+        )];
+        vertices.extend(corners.iter().zip(colors).map(|(corner, color)| {
+            Vertex::new(
+                transform.transform_point(*corner).to_array(),
+                [0.0, 0.0],
+                color,
+            )
+        }));
 
-        let indices = vec![0, 1, 3, 3, 1, 2];
+        let indices = vec![0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 1];
 
         (vertices, indices)
     }
 
+    /// Map `sub_coords` of `texture`, in texture pixels, onto the rect from `from` to `to`. With
+    /// `y_up` the texture coordinates are measured from the bottom-left corner of the texture,
+    /// and its bottom row is drawn at `from`.
     pub(crate) fn texture_geometry(
         &self,
         texture: &Texture,
         sub_coords: Option<(Vec2, Vec2)>,
-        pixel_size: Vec2,
+        color: Pixel,
+        y_up: bool,
+        transform: Transform,
     ) -> (Vec<Vertex>, Vec<u32>) {
-        let fill_color = Pixel::WHITE.to_array();
+        let fill_color = color.to_array();
 
-        let (tex_from, tex_to) = sub_coords.unwrap_or((
-            v2(0.0, 0.0),
-            v2(texture.width() as f32, texture.height() as f32),
-        ));
+        let texture_height = texture.height() as f32;
+        let (mut tex_from, mut tex_to) =
+            sub_coords.unwrap_or((v2(0.0, 0.0), v2(texture.width() as f32, texture_height)));
+
+        if y_up {
+            tex_from.y = texture_height - tex_from.y;
+            tex_to.y = texture_height - tex_to.y;
+        }
 
         // Normalize texture coords.
         let tex_left = tex_from.x / texture.width() as f32;
@@ -182,7 +267,7 @@ impl RectangleShape {
         let mut vertices = Vec::with_capacity(self.point_count);
 
         for i in 0..self.point_count {
-            let coords = self.point(i as usize);
+            let coords = self.point(i);
 
             let ratio_x = (coords.x - self.from.x) / self.width();
             let ratio_y = (coords.y - self.from.y) / self.height();
@@ -193,7 +278,7 @@ impl RectangleShape {
             );
 
             vertices.push(Vertex {
-                coords: (coords * pixel_size).to_array(),
+                coords: transform.transform_point(coords).to_array(),
                 tex_coords: tex_coords.to_array(),
                 fill_color,
             });
@@ -204,3 +289,1178 @@ impl RectangleShape {
         (vertices, indices)
     }
 }
+
+/// What each point drawn with [`Scene::draw_points`](crate::Scene::draw_points) looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointStyle {
+    Square,
+    Round,
+}
+
+/// Many points of the same size and style, each centered on its position.
+pub(crate) struct PointsShape<'a> {
+    points: &'a [(Vec2, Pixel)],
+    size: f32,
+}
+
+impl<'a> PointsShape<'a> {
+    pub(crate) fn new(points: &'a [(Vec2, Pixel)], size: f32) -> Self {
+        Self { points, size }
+    }
+
+    /// One triangle list for all the points.
+    pub(crate) fn fill_geometry(
+        &self,
+        style: PointStyle,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let half = self.size.abs() / 2.0;
+
+        // Every point has the same outline, so it is only worked out once.
+        let (outline, indices): (Vec<Vec2>, Vec<u32>) = match style {
+            PointStyle::Square => (
+                vec![
+                    v2(-half, -half),
+                    v2(half, -half),
+                    v2(half, half),
+                    v2(-half, half),
+                ],
+                vec![0, 1, 2, 0, 2, 3],
+            ),
+            PointStyle::Round => {
+                let outline = EllipseShape::new(v2(0.0, 0.0), v2(half, half)).points(transform);
+                let count = outline.len() as u32;
+                let indices = (1..count.saturating_sub(1))
+                    .flat_map(|i| [0, i, i + 1])
+                    .collect();
+
+                (outline, indices)
+            }
+        };
+
+        let mut vertices = Vec::with_capacity(self.points.len() * outline.len());
+        let mut all_indices = Vec::with_capacity(self.points.len() * indices.len());
+
+        for (position, color) in self.points {
+            let first = vertices.len() as u32;
+            let fill_color = color.to_array();
+
+            vertices.extend(outline.iter().map(|offset| {
+                Vertex::new(
+                    transform.transform_point(*position + *offset).to_array(),
+                    [0.0, 0.0],
+                    fill_color,
+                )
+            }));
+            all_indices.extend(indices.iter().map(|i| first + i));
+        }
+
+        (vertices, all_indices)
+    }
+}
+
+pub(crate) struct EllipseShape {
+    center: Vec2,
+    radii: Vec2,
+    start_angle: f32,
+    sweep_angle: f32,
+}
+
+impl EllipseShape {
+    /// Largest distance, in physical pixels, between the true curve and its segments.
+    const TOLERANCE: f32 = 0.25;
+    const MIN_SEGMENTS: usize = 8;
+    const MAX_SEGMENTS: usize = 1024;
+
+    pub(crate) fn new(center: Vec2, radii: Vec2) -> Self {
+        Self::arc(center, radii, 0.0, TAU)
+    }
+
+    /// Part of an ellipse, with angles in radians measured from the x-axis towards the y-axis.
+    pub(crate) fn arc(center: Vec2, radii: Vec2, start_angle: f32, sweep_angle: f32) -> Self {
+        Self {
+            center,
+            radii,
+            start_angle,
+            sweep_angle: sweep_angle.clamp(-TAU, TAU),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sweep_angle.abs() >= TAU
+    }
+
+    /// Segments needed for the curve to look smooth at its size on screen.
+    pub(crate) fn segment_count(&self, transform: Transform) -> usize {
+        let radius = f32::max(self.radii.x.abs(), self.radii.y.abs()) * transform.max_scale();
+
+        if radius <= Self::TOLERANCE {
+            return Self::MIN_SEGMENTS;
+        }
+
+        let step = 2.0 * (1.0 - Self::TOLERANCE / radius).acos();
+        let count = (self.sweep_angle.abs() / step).ceil() as usize;
+
+        count.clamp(Self::MIN_SEGMENTS, Self::MAX_SEGMENTS)
+    }
+
+    /// Points along the curve, ordered so outlines are extruded away from the center. Closed
+    /// ellipses don't repeat their first point. None when there is no curve to follow.
+    pub(crate) fn points(&self, transform: Transform) -> Vec<Vec2> {
+        if self.radii == v2(0.0, 0.0) || self.sweep_angle == 0.0 {
+            return Vec::new();
+        }
+
+        let segments = self.segment_count(transform);
+        let count = if self.is_closed() {
+            segments
+        } else {
+            segments + 1
+        };
+
+        let end_angle = self.start_angle + self.sweep_angle;
+        let high = f32::max(self.start_angle, end_angle);
+        let sweep = self.sweep_angle.abs();
+
+        (0..count)
+            .map(|i| {
+                let angle = high - sweep * i as f32 / segments as f32;
+                self.center + v2(angle.cos(), angle.sin()) * self.radii
+            })
+            .collect()
+    }
+
+    /// A filled ellipse, or a pie slice when only part of it is swept.
+    pub(crate) fn fill_geometry(
+        &self,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let fill_color = color.to_array();
+        let points = self.points(transform);
+
+        if points.is_empty() {
+            return (Vec::new(), Vec::new());
+        }
+
+        let mut vertices = Vec::with_capacity(points.len() + 1);
+        vertices.push(Vertex::new(
+            transform.transform_point(self.center).to_array(),
+            [0.0, 0.0],
+            fill_color,
+        ));
+        vertices.extend(points.iter().map(|point| {
+            Vertex::new(
+                transform.transform_point(*point).to_array(),
+                [0.0, 0.0],
+                fill_color,
+            )
+        }));
+
+        let triangles = if self.is_closed() {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+
+        let mut indices = Vec::with_capacity(triangles * 3);
+        for i in 0..triangles {
+            indices.push(0);
+            indices.push(1 + i as u32);
+            indices.push(1 + ((i + 1) % points.len()) as u32);
+        }
+
+        (vertices, indices)
+    }
+
+    /// The outline of the ellipse, or just the curve when only part of it is swept.
+    pub(crate) fn outline_geometry(
+        &self,
+        thickness: f32,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let points = self.points(transform);
+
+        outline_geometry(&points, self.is_closed(), thickness, color, transform)
+    }
+
+    /// The outline of a pie slice, including the edges to the center.
+    pub(crate) fn sector_outline_geometry(
+        &self,
+        thickness: f32,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        if self.is_closed() {
+            return self.outline_geometry(thickness, color, transform);
+        }
+
+        let mut points = self.points(transform);
+        points.push(self.center);
+
+        outline_geometry(&points, true, thickness, color, transform)
+    }
+}
+
+pub(crate) struct PolygonShape<'a> {
+    points: &'a [Vec2],
+    holes: &'a [Vec<Vec2>],
+}
+
+impl<'a> PolygonShape<'a> {
+    pub(crate) fn new(points: &'a [Vec2], holes: &'a [Vec<Vec2>]) -> Self {
+        Self { points, holes }
+    }
+
+    pub(crate) fn fill_geometry(
+        &self,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let fill_color = color.to_array();
+
+        let indices = triangulate(self.points, self.holes);
+
+        if indices.is_empty() {
+            return (Vec::new(), Vec::new());
+        }
+
+        let vertices = self
+            .points
+            .iter()
+            .chain(self.holes.iter().filter(|hole| hole.len() >= 3).flatten())
+            .map(|point| {
+                Vertex::new(
+                    transform.transform_point(*point).to_array(),
+                    [0.0, 0.0],
+                    fill_color,
+                )
+            })
+            .collect();
+
+        (vertices, indices)
+    }
+
+    /// One triangle strip for the boundary and one for each hole. Holes are extruded into the
+    /// hole so the outline stays clear of the filled area.
+    pub(crate) fn outline_geometry(
+        &self,
+        thickness: f32,
+        color: Pixel,
+        transform: Transform,
+    ) -> Vec<(Vec<Vertex>, Vec<u32>)> {
+        let boundary = outline_geometry(self.points, true, thickness, color, transform);
+        let holes = self
+            .holes
+            .iter()
+            .filter(|hole| hole.len() >= 3)
+            .map(|hole| outline_geometry(hole, true, -thickness, color, transform));
+
+        std::iter::once(boundary).chain(holes).collect()
+    }
+}
+
+pub(crate) struct PolylineShape<'a> {
+    points: &'a [Vec2],
+    closed: bool,
+}
+
+impl<'a> PolylineShape<'a> {
+    pub(crate) fn new(points: &'a [Vec2], closed: bool) -> Self {
+        Self { points, closed }
+    }
+
+    /// A triangle list centered on the line. Where segments overlap on the inside of a corner
+    /// they are drawn twice, which shows with translucent colors.
+    pub(crate) fn stroke_geometry(
+        &self,
+        stroke: &Stroke,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let mut points: Vec<Vec2> = Vec::with_capacity(self.points.len());
+        for point in self.points {
+            if points.last() != Some(point) {
+                points.push(*point);
+            }
+        }
+
+        if self.closed && points.len() > 2 && points.first() == points.last() {
+            points.pop();
+        }
+
+        let mut builder = GeometryBuilder::new(color, transform);
+        let half = stroke.thickness / 2.0;
+
+        if points.len() < 2 || half <= 0.0 {
+            return builder.build();
+        }
+
+        match dashes(
+            &points,
+            self.closed,
+            &stroke.dashes,
+            stroke.dash_offset,
+            transform.max_scale(),
+        ) {
+            Some(dashes) => {
+                for (dash, direction) in dashes {
+                    if dash.len() == 1 {
+                        builder.dot(dash[0], direction, half, stroke.cap);
+                    } else {
+                        builder.polyline(&dash, false, half, stroke);
+                    }
+                }
+            }
+            None => builder.polyline(&points, self.closed, half, stroke),
+        }
+
+        builder.build()
+    }
+}
+
+/// Split a line into the parts that are drawn by a dash pattern, each with the direction it
+/// starts in. Dashes of no length come out as a single point. `None` when the pattern leaves
+/// the line solid, which includes patterns shorter than a pixel at `scale` and lines too long
+/// for their pattern.
+fn dashes(
+    points: &[Vec2],
+    closed: bool,
+    pattern: &[f32],
+    offset: f32,
+    scale: f32,
+) -> Option<Vec<(Vec<Vec2>, Vec2)>> {
+    /// Most times a pattern is repeated along one line before it is drawn solid instead.
+    const MAX_REPEATS: f32 = 100_000.0;
+
+    // An odd number of lengths is repeated, so dashes and gaps swap over the second time.
+    let pattern: Vec<f32> = if pattern.len() % 2 == 1 {
+        pattern.iter().chain(pattern).copied().collect()
+    } else {
+        pattern.to_vec()
+    };
+
+    let total: f32 = pattern.iter().sum();
+    if pattern
+        .iter()
+        .any(|length| !length.is_finite() || *length < 0.0)
+        || !total.is_finite()
+        || total <= 0.0
+        || total * scale < 1.0
+    {
+        return None;
+    }
+
+    let segments = points
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .chain(closed.then(|| (points[points.len() - 1], points[0])));
+
+    let line_length: f32 = segments.clone().map(|(from, to)| (to - from).len()).sum();
+    if !line_length.is_finite() || line_length / total > MAX_REPEATS {
+        return None;
+    }
+
+    let mut position = offset.rem_euclid(total);
+    let mut index = 0;
+    while position >= pattern[index] && position > 0.0 {
+        position -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+    let mut remaining = pattern[index] - position;
+
+    let mut dashes = Vec::new();
+    let mut dash: Option<(Vec<Vec2>, Vec2)> = None;
+    let starts_drawn = index % 2 == 0;
+
+    for (from, to) in segments {
+        let length = (to - from).len();
+        let direction = (to - from) / length;
+        let mut travelled = 0.0;
+
+        if index % 2 == 0 && dash.is_none() {
+            dash = Some((vec![from], direction));
+        }
+
+        while remaining <= length - travelled {
+            travelled += remaining;
+            let point = from + direction * travelled;
+
+            match dash.take() {
+                Some((mut dash_points, start)) => {
+                    if dash_points.last() != Some(&point) {
+                        dash_points.push(point);
+                    }
+                    dashes.push((dash_points, start));
+                }
+                None => dash = Some((vec![point], direction)),
+            }
+
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+
+        remaining -= length - travelled;
+        if let Some((dash_points, _)) = &mut dash {
+            if dash_points.last() != Some(&to) {
+                dash_points.push(to);
+            }
+        }
+    }
+
+    if let Some(last) = dash {
+        // On a closed line a dash running over the start joins up with the first one.
+        if closed && starts_drawn && !dashes.is_empty() {
+            let (first, _) = &mut dashes[0];
+            let mut joined = last.0;
+            joined.extend(first.iter().skip(1));
+            *first = joined;
+            dashes[0].1 = last.1;
+        } else {
+            dashes.push(last);
+        }
+    }
+
+    Some(dashes)
+}
+
+pub(crate) struct PathShape<'a> {
+    path: &'a Path,
+}
+
+impl<'a> PathShape<'a> {
+    pub(crate) fn new(path: &'a Path) -> Self {
+        Self { path }
+    }
+
+    /// The area inside the path, with every sub-path treated as closed.
+    pub(crate) fn fill_geometry(
+        &self,
+        rule: FillRule,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let fill_color = color.to_array();
+
+        let contours: Vec<Vec<Vec2>> = self
+            .path
+            .flatten(transform)
+            .into_iter()
+            .map(|(points, _)| points)
+            .collect();
+        let (points, indices) = fill_trapezoids(&contours, rule);
+
+        let vertices = points
+            .into_iter()
+            .map(|point| {
+                Vertex::new(
+                    transform.transform_point(point).to_array(),
+                    [0.0, 0.0],
+                    fill_color,
+                )
+            })
+            .collect();
+
+        (vertices, indices)
+    }
+
+    pub(crate) fn stroke_geometry(
+        &self,
+        stroke: &Stroke,
+        color: Pixel,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        for (points, closed) in self.path.flatten(transform) {
+            let polyline = PolylineShape::new(&points, closed);
+            let (sub_vertices, sub_indices) = polyline.stroke_geometry(stroke, color, transform);
+
+            let offset = vertices.len() as u32;
+            vertices.extend(sub_vertices);
+            indices.extend(sub_indices.into_iter().map(|i| i + offset));
+        }
+
+        (vertices, indices)
+    }
+}
+
+/// Collects triangles, transforming their points on the way in.
+struct GeometryBuilder {
+    fill_color: [f32; 4],
+    transform: Transform,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+}
+
+impl GeometryBuilder {
+    fn new(color: Pixel, transform: Transform) -> Self {
+        Self {
+            fill_color: color.to_array(),
+            transform,
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    fn vertex(&mut self, point: Vec2) -> u32 {
+        self.vertices.push(Vertex::new(
+            self.transform.transform_point(point).to_array(),
+            [0.0, 0.0],
+            self.fill_color,
+        ));
+
+        self.vertices.len() as u32 - 1
+    }
+
+    fn quad(&mut self, a: Vec2, b: Vec2, c: Vec2, d: Vec2) {
+        let a = self.vertex(a);
+        let b = self.vertex(b);
+        let c = self.vertex(c);
+        let d = self.vertex(d);
+
+        self.indices.extend([a, b, c, a, c, d]);
+    }
+
+    /// Triangles from `center` to each consecutive pair of `points`.
+    fn fan(&mut self, center: Vec2, points: &[Vec2]) {
+        let center = self.vertex(center);
+        let first = self.vertices.len() as u32;
+
+        for point in points {
+            self.vertex(*point);
+        }
+
+        for i in 1..points.len() as u32 {
+            self.indices.extend([center, first + i - 1, first + i]);
+        }
+    }
+
+    /// Segments of a line centered on `points`, with the joins between them and caps on the
+    /// ends of open lines.
+    fn polyline(&mut self, points: &[Vec2], closed: bool, half: f32, stroke: &Stroke) {
+        let segment_count = if closed {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+
+        for i in 0..segment_count {
+            let mut from = points[i];
+            let mut to = points[(i + 1) % points.len()];
+            let direction = (to - from).norm();
+
+            if !closed && stroke.cap == LineCap::Square {
+                if i == 0 {
+                    from -= direction * half;
+                }
+
+                if i == segment_count - 1 {
+                    to += direction * half;
+                }
+            }
+
+            let normal = direction.perp_cw() * half;
+            self.quad(from + normal, from - normal, to - normal, to + normal);
+        }
+
+        let joins = if closed {
+            0..points.len()
+        } else {
+            1..points.len() - 1
+        };
+
+        for i in joins {
+            let previous = points[(i + points.len() - 1) % points.len()];
+            let next = points[(i + 1) % points.len()];
+
+            self.join(
+                points[i],
+                (points[i] - previous).norm(),
+                (next - points[i]).norm(),
+                half,
+                stroke,
+            );
+        }
+
+        if !closed && stroke.cap == LineCap::Round {
+            let start = points[0];
+            let end = points[points.len() - 1];
+
+            self.round_cap(start, (start - points[1]).norm(), half);
+            self.round_cap(end, (end - points[points.len() - 2]).norm(), half);
+        }
+    }
+
+    /// What a dash of no length leaves with the given cap: a circle, a square turned to face
+    /// `direction`, or nothing.
+    fn dot(&mut self, point: Vec2, direction: Vec2, half: f32, cap: LineCap) {
+        match cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                let along = direction * half;
+                let normal = direction.perp_cw() * half;
+                self.quad(
+                    point - along + normal,
+                    point - along - normal,
+                    point + along - normal,
+                    point + along + normal,
+                );
+            }
+            LineCap::Round => {
+                self.round_cap(point, direction, half);
+                self.round_cap(point, -direction, half);
+            }
+        }
+    }
+
+    /// Fill the gap on the outside of a corner at `point`, between a segment arriving in
+    /// direction `incoming` and one leaving in direction `outgoing`.
+    fn join(&mut self, point: Vec2, incoming: Vec2, outgoing: Vec2, half: f32, stroke: &Stroke) {
+        let turn = incoming.x * outgoing.y - incoming.y * outgoing.x;
+
+        if turn == 0.0 && incoming.dot(outgoing) > 0.0 {
+            return;
+        }
+
+        // The outside of the corner is on the opposite side to the way the line turns.
+        let side = if turn > 0.0 { 1.0 } else { -1.0 };
+        let from = incoming.perp_cw() * side;
+        let to = outgoing.perp_cw() * side;
+
+        match stroke.join {
+            LineJoin::Miter | LineJoin::Bevel => {
+                let bisector = from + to;
+                let cos_half = if bisector.len() > 0.0 {
+                    bisector.norm().dot(from)
+                } else {
+                    0.0
+                };
+
+                if stroke.join == LineJoin::Miter
+                    && cos_half > 0.0
+                    && 1.0 / cos_half <= stroke.miter_limit
+                {
+                    let tip = point + bisector.norm() * (half / cos_half);
+                    self.fan(point, &[point + from * half, tip, point + to * half]);
+                } else {
+                    self.fan(point, &[point + from * half, point + to * half]);
+                }
+            }
+
+            LineJoin::Round => {
+                let start_angle = from.y.atan2(from.x);
+                let sweep_angle = turn.atan2(incoming.dot(outgoing)).abs() * side;
+                self.arc(point, half, start_angle, sweep_angle);
+            }
+        }
+    }
+
+    /// A half circle past `point`, facing `direction`.
+    fn round_cap(&mut self, point: Vec2, direction: Vec2, half: f32) {
+        let normal = direction.perp_cw();
+        self.arc(point, half, normal.y.atan2(normal.x), PI);
+    }
+
+    fn arc(&mut self, center: Vec2, radius: f32, start_angle: f32, sweep_angle: f32) {
+        let arc = EllipseShape::arc(center, v2(radius, radius), start_angle, sweep_angle);
+        let points = arc.points(self.transform);
+
+        self.fan(center, &points);
+    }
+
+    fn build(self) -> (Vec<Vertex>, Vec<u32>) {
+        (self.vertices, self.indices)
+    }
+}
+
+/// Extrude a line of points by `thickness` as a triangle strip. Closed outlines are extruded
+/// outwards whichever way they wind, or inwards for a negative `thickness`; open ones to the
+/// right of their direction of travel, as seen with y pointing down.
+pub(crate) fn outline_geometry(
+    points: &[Vec2],
+    closed: bool,
+    thickness: f32,
+    color: Pixel,
+    transform: Transform,
+) -> (Vec<Vertex>, Vec<u32>) {
+    let point_count = points.len();
+
+    if point_count < 2 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let thickness = if closed && signed_area(points) > 0.0 {
+        -thickness
+    } else {
+        thickness
+    };
+
+    let mut vertices: Vec<Vertex> = Vec::with_capacity((point_count + 1) * 2);
+    let mut indices = Vec::with_capacity((point_count + 1) * 2);
+
+    for i in 0..point_count {
+        // https://stackoverflow.com/questions/69631855/extrude-2d-vertices-vectors
+
+        // Get the normals of the vectors either side of the current point (the in and out vectors).
+        let p0 = points[i];
+        let p1 = (closed || i + 1 < point_count).then(|| points[(i + 1) % point_count]);
+        let p2 = (closed || i > 0).then(|| points[(i + point_count - 1) % point_count]);
+
+        let v_in = p1.map(|p1| (p0 - p1).perp_cw().norm());
+        let v_out = p2.map(|p2| (p2 - p0).perp_cw().norm());
+
+        // The ends of an open outline only have one neighbour.
+        let (v_in, v_out) = match (v_in, v_out) {
+            (Some(v_in), Some(v_out)) => (v_in, v_out),
+            (Some(v), None) | (None, Some(v)) => (v, v),
+            (None, None) => unreachable!("outlines have at least 2 points"),
+        };
+
+        // Bisect the normals.
+        let mut bisector = v_in + v_out;
+        bisector /= bisector.dot(v_in).abs();
+        bisector *= thickness;
+
+        // Add the original vertex and the extruded vertex to the geometry.
+        vertices.push(Vertex::new(
+            transform.transform_point(p0).to_array(),
+            [0.0, 0.0],
+            color.to_array(),
+        ));
+        vertices.push(Vertex::new(
+            transform.transform_point(p0 + bisector).to_array(),
+            [0.0, 0.0],
+            color.to_array(),
+        ));
+
+        // And the indices for each.
+        indices.push(i as u32 * 2);
+        indices.push(i as u32 * 2 + 1);
+    }
+
+    // Close the outline.
+    if closed {
+        indices.push(indices[0]);
+        indices.push(indices[1]);
+    }
+
+    (vertices, indices)
+}
+
+/// Twice the signed area of a polygon, negative when it winds the same way as a rectangle's
+/// points.
+pub(crate) fn signed_area(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|i| {
+            let p0 = points[i];
+            let p1 = points[(i + 1) % points.len()];
+            p0.x * p1.y - p1.x * p0.y
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    use super::*;
+
+    #[test]
+    fn closed_outline_is_extruded_outwards_either_way_round() {
+        let square = [v2(0.0, 0.0), v2(0.0, 10.0), v2(10.0, 10.0), v2(10.0, 0.0)];
+        let mut reversed = square;
+        reversed.reverse();
+
+        for points in [square, reversed] {
+            let (vertices, indices) =
+                outline_geometry(&points, true, 1.0, Pixel::WHITE, Transform::IDENTITY);
+
+            let corner = vertices
+                .chunks(2)
+                .find(|pair| pair[0].coords == [0.0, 0.0])
+                .expect("corner");
+            assert_eq!([-1.0, -1.0], corner[1].coords);
+            assert_eq!(10, indices.len());
+        }
+    }
+
+    #[test]
+    fn open_outline_is_not_closed() {
+        let points = [v2(0.0, 0.0), v2(10.0, 0.0), v2(20.0, 0.0)];
+        let (vertices, indices) =
+            outline_geometry(&points, false, 2.0, Pixel::WHITE, Transform::IDENTITY);
+
+        assert_eq!(6, vertices.len());
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], indices);
+        assert_eq!([0.0, 2.0], vertices[1].coords);
+        assert_eq!([20.0, 2.0], vertices[5].coords);
+    }
+
+    #[test]
+    fn negative_thickness_extrudes_closed_outline_inwards() {
+        let square = [v2(0.0, 0.0), v2(0.0, 10.0), v2(10.0, 10.0), v2(10.0, 0.0)];
+        let (vertices, _) =
+            outline_geometry(&square, true, -1.0, Pixel::WHITE, Transform::IDENTITY);
+
+        assert_eq!([1.0, 1.0], vertices[1].coords);
+    }
+
+    #[test]
+    fn polygon_fill_includes_hole_vertices() {
+        let square = [v2(0.0, 0.0), v2(0.0, 10.0), v2(10.0, 10.0), v2(10.0, 0.0)];
+        let holes = vec![vec![v2(4.0, 4.0), v2(6.0, 4.0), v2(6.0, 6.0), v2(4.0, 6.0)]];
+        let polygon = PolygonShape::new(&square, &holes);
+        let (vertices, indices) = polygon.fill_geometry(Pixel::WHITE, Transform::IDENTITY);
+
+        assert_eq!(8, vertices.len());
+        assert_eq!(8 * 3, indices.len());
+    }
+
+    #[test]
+    fn polygon_outline_has_a_strip_per_ring() {
+        let square = [v2(0.0, 0.0), v2(0.0, 10.0), v2(10.0, 10.0), v2(10.0, 0.0)];
+        let holes = vec![vec![v2(4.0, 4.0), v2(6.0, 4.0), v2(6.0, 6.0), v2(4.0, 6.0)]];
+        let polygon = PolygonShape::new(&square, &holes);
+        let strips = polygon.outline_geometry(1.0, Pixel::WHITE, Transform::IDENTITY);
+
+        assert_eq!(2, strips.len());
+        assert_eq!([-1.0, -1.0], strips[0].0[1].coords);
+        assert_eq!([5.0, 5.0], strips[1].0[1].coords);
+    }
+
+    #[test]
+    fn corner_colors_blend_through_center() {
+        let rect = RectangleShape::new(v2(0.0, 0.0), v2(10.0, 10.0));
+        let (vertices, indices) = rect.corner_fill_geometry(
+            [Pixel::BLACK, Pixel::WHITE, Pixel::BLACK, Pixel::WHITE],
+            Transform::IDENTITY,
+        );
+
+        assert_eq!([5.0, 5.0], vertices[0].coords);
+        assert_eq!([0.5, 0.5, 0.5, 1.0], vertices[0].fill_color);
+        assert_eq!([10.0, 0.0], vertices[2].coords);
+        assert_eq!(Pixel::WHITE.to_array(), vertices[2].fill_color);
+        assert_eq!(12, indices.len());
+    }
+
+    #[test]
+    fn rounded_rect_corners_are_arcs() {
+        let rect = RectangleShape::rounded(v2(0.0, 0.0), v2(40.0, 20.0), [5.0, 0.0, 30.0, 0.0]);
+        let points = rect.points(Transform::IDENTITY);
+
+        assert_eq!(v2(5.0, 0.0), points[0]);
+        assert!(points.contains(&v2(40.0, 0.0)));
+        assert!(points.contains(&v2(0.0, 20.0)));
+
+        // The bottom-right radius is limited to half the height.
+        for point in points.iter().filter(|p| p.x > 30.0 && p.y > 10.0) {
+            assert!(((*point - v2(30.0, 10.0)).len() - 10.0).abs() < 1e-3);
+        }
+        for point in points.iter().filter(|p| p.x < 5.0 && p.y < 5.0) {
+            assert!(((*point - v2(5.0, 5.0)).len() - 5.0).abs() < 1e-3);
+        }
+
+        let (vertices, indices) = rect.fill_geometry(Pixel::WHITE, Transform::IDENTITY);
+        assert_eq!(points.len(), vertices.len());
+        assert_eq!((points.len() - 2) * 3, indices.len());
+    }
+
+    #[test]
+    fn fully_rounded_rect_outlines_stay_around_the_rect() {
+        for (to, radius) in [(v2(40.0, 20.0), 10.0), (v2(20.0, 20.0), 10.0)] {
+            let rect = RectangleShape::rounded(v2(0.0, 0.0), to, [radius; 4]);
+            let points = rect.points(Transform::IDENTITY);
+
+            for (i, point) in points.iter().enumerate() {
+                let next = points[(i + 1) % points.len()];
+                assert!((next - *point).len() > 1e-3);
+            }
+
+            let (vertices, _) = rect.outline_geometry(2.0, Pixel::WHITE, Transform::IDENTITY);
+            let (min, max) = bounds(&vertices);
+
+            assert!(vertices
+                .iter()
+                .all(|v| v.coords.iter().all(|c| c.is_finite())));
+            assert!(min.x >= -2.01 && min.y >= -2.01);
+            assert!(max.x <= to.x + 2.01 && max.y <= to.y + 2.01);
+        }
+    }
+
+    #[test]
+    fn dashes_follow_pattern_and_offset() {
+        let points = [v2(0.0, 0.0), v2(10.0, 0.0), v2(10.0, 10.0)];
+        let parts = dashes(&points, false, &[4.0, 2.0], 3.0, 1.0).unwrap();
+
+        let starts: Vec<Vec2> = parts.iter().map(|(dash, _)| dash[0]).collect();
+        assert_eq!(
+            vec![v2(0.0, 0.0), v2(3.0, 0.0), v2(9.0, 0.0), v2(10.0, 5.0)],
+            starts
+        );
+        // The third dash turns the corner.
+        assert_eq!(vec![v2(9.0, 0.0), v2(10.0, 0.0), v2(10.0, 3.0)], parts[2].0);
+        assert_eq!(vec![v2(10.0, 5.0), v2(10.0, 9.0)], parts[3].0);
+
+        assert!(dashes(&points, false, &[], 0.0, 1.0).is_none());
+        assert!(dashes(&points, false, &[0.0, 0.0], 0.0, 1.0).is_none());
+    }
+
+    #[test]
+    fn dense_dashes_are_drawn_solid() {
+        let points = [v2(0.0, 0.0), v2(100_000.0, 0.0)];
+        let polyline = PolylineShape::new(&points, false);
+
+        let fine = Stroke::new(2.0).with_dashes(&[0.001, 0.001]);
+        let solid = Stroke::new(2.0);
+        assert_eq!(
+            polyline.stroke_geometry(&solid, Pixel::WHITE, Transform::IDENTITY),
+            polyline.stroke_geometry(&fine, Pixel::WHITE, Transform::IDENTITY)
+        );
+
+        // Zoomed in far enough the pattern shows, as long as the line isn't too long for it.
+        let short = [v2(0.0, 0.0), v2(1.0, 0.0)];
+        let parts = dashes(&short, false, &[0.001, 0.001], 0.0, 1000.0).unwrap();
+        assert!((499..=501).contains(&parts.len()));
+        assert!(dashes(&points, false, &[0.001, 0.001], 0.0, 1000.0).is_none());
+        assert!(dashes(&points, false, &[1.0, 1.0], 0.0, 1.0).is_some());
+    }
+
+    #[test]
+    fn closed_dashes_join_over_the_start() {
+        let points = [v2(0.0, 0.0), v2(10.0, 0.0), v2(10.0, 10.0), v2(0.0, 10.0)];
+        let parts = dashes(&points, true, &[6.0, 4.0], 2.0, 1.0).unwrap();
+
+        assert_eq!(4, parts.len());
+        assert_eq!(vec![v2(0.0, 2.0), v2(0.0, 0.0), v2(4.0, 0.0)], parts[0].0);
+    }
+
+    #[test]
+    fn zero_length_dashes_are_dots() {
+        let points = [v2(0.0, 0.0), v2(10.0, 0.0)];
+        let polyline = PolylineShape::new(&points, false);
+
+        let dotted = Stroke::new(2.0)
+            .with_cap(LineCap::Round)
+            .with_dashes(&[0.0, 5.0]);
+        let (vertices, _) = polyline.stroke_geometry(&dotted, Pixel::WHITE, Transform::IDENTITY);
+        let (min, max) = bounds(&vertices);
+        assert!((min.x + 1.0).abs() < 1e-4 && (max.x - 11.0).abs() < 1e-4);
+
+        let butt = dotted.with_cap(LineCap::Butt);
+        let (vertices, _) = polyline.stroke_geometry(&butt, Pixel::WHITE, Transform::IDENTITY);
+        assert!(vertices.is_empty());
+    }
+
+    #[test]
+    fn points_share_one_batch() {
+        let points = [(v2(0.0, 0.0), Pixel::RED), (v2(10.0, 10.0), Pixel::GREEN)];
+        let shape = PointsShape::new(&points, 2.0);
+
+        let (vertices, indices) = shape.fill_geometry(PointStyle::Square, Transform::IDENTITY);
+        assert_eq!(8, vertices.len());
+        assert_eq!(vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7], indices);
+        assert_eq!([-1.0, -1.0], vertices[0].coords);
+        assert_eq!([11.0, 11.0], vertices[6].coords);
+        assert_eq!(Pixel::GREEN.to_array(), vertices[4].fill_color);
+
+        let (vertices, indices) = shape.fill_geometry(PointStyle::Round, Transform::IDENTITY);
+        assert_eq!(0, vertices.len() % 2);
+        assert_eq!((vertices.len() / 2 - 2) * 6, indices.len());
+        for vertex in &vertices[vertices.len() / 2..] {
+            let offset = v2(vertex.coords[0], vertex.coords[1]) - v2(10.0, 10.0);
+            assert!((offset.len() - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn line_is_centered() {
+        let line = LineShape::new(v2(0.0, 0.0), v2(10.0, 0.0));
+        let (vertices, _) = line.fill_geometry(2.0, Pixel::WHITE, Transform::IDENTITY);
+
+        let mut ys: Vec<f32> = vertices.iter().map(|v| v.coords[1]).collect();
+        ys.sort_by(f32::total_cmp);
+        assert_eq!(vec![-1.0, -1.0, 1.0, 1.0], ys);
+    }
+
+    fn bounds(vertices: &[Vertex]) -> (Vec2, Vec2) {
+        vertices.iter().fold(
+            (v2(f32::MAX, f32::MAX), v2(f32::MIN, f32::MIN)),
+            |(min, max), v| {
+                (
+                    v2(min.x.min(v.coords[0]), min.y.min(v.coords[1])),
+                    v2(max.x.max(v.coords[0]), max.y.max(v.coords[1])),
+                )
+            },
+        )
+    }
+
+    #[test]
+    fn polyline_caps_extend_past_ends() {
+        let points = [v2(0.0, 0.0), v2(10.0, 0.0)];
+        let polyline = PolylineShape::new(&points, false);
+
+        for (cap, extent) in [
+            (LineCap::Butt, 0.0),
+            (LineCap::Square, 1.0),
+            (LineCap::Round, 1.0),
+        ] {
+            let stroke = Stroke::new(2.0).with_cap(cap);
+            let (vertices, _) =
+                polyline.stroke_geometry(&stroke, Pixel::WHITE, Transform::IDENTITY);
+            let (min, max) = bounds(&vertices);
+
+            assert!((min.x + extent).abs() < 1e-4, "{cap:?}");
+            assert!((max.x - 10.0 - extent).abs() < 1e-4, "{cap:?}");
+            assert!((min.y + 1.0).abs() < 1e-4 && (max.y - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn miter_join_reaches_corner_within_limit() {
+        let points = [v2(0.0, 0.0), v2(10.0, 0.0), v2(10.0, 10.0)];
+        let polyline = PolylineShape::new(&points, false);
+
+        let has_tip = |vertices: &[Vertex]| {
+            vertices
+                .iter()
+                .any(|v| (v2(v.coords[0], v.coords[1]) - v2(11.0, -1.0)).len() < 1e-4)
+        };
+
+        let (vertices, _) =
+            polyline.stroke_geometry(&Stroke::new(2.0), Pixel::WHITE, Transform::IDENTITY);
+        assert!(has_tip(&vertices));
+
+        let stroke = Stroke::new(2.0).with_miter_limit(1.0);
+        let (vertices, _) = polyline.stroke_geometry(&stroke, Pixel::WHITE, Transform::IDENTITY);
+        assert!(!has_tip(&vertices));
+    }
+
+    #[test]
+    fn round_join_stays_within_thickness() {
+        let points = [v2(0.0, 0.0), v2(10.0, 0.0), v2(10.0, 10.0)];
+        let polyline = PolylineShape::new(&points, false);
+        let stroke = Stroke::new(2.0).with_join(LineJoin::Round);
+        let (vertices, indices) =
+            polyline.stroke_geometry(&stroke, Pixel::WHITE, Transform::IDENTITY);
+
+        assert_eq!(0, indices.len() % 3);
+        assert!(vertices.iter().any(|v| {
+            (v2(v.coords[0], v.coords[1]) - v2(10.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2)).len() < 0.2
+        }));
+    }
+
+    #[test]
+    fn closed_polyline_joins_every_point() {
+        let square = [v2(0.0, 0.0), v2(10.0, 0.0), v2(10.0, 10.0), v2(0.0, 10.0)];
+        let open = PolylineShape::new(&square, false);
+        let closed = PolylineShape::new(&square, true);
+        let stroke = Stroke::new(2.0).with_join(LineJoin::Bevel);
+
+        let (_, open_indices) = open.stroke_geometry(&stroke, Pixel::WHITE, Transform::IDENTITY);
+        let (_, closed_indices) =
+            closed.stroke_geometry(&stroke, Pixel::WHITE, Transform::IDENTITY);
+
+        // 3 segments and 2 joins, against 4 segments and 4 joins.
+        assert_eq!((3 * 2 + 2) * 3, open_indices.len());
+        assert_eq!((4 * 2 + 4) * 3, closed_indices.len());
+    }
+
+    #[test]
+    fn path_stroke_combines_sub_paths() {
+        let path = Path::new()
+            .move_to([0.0, 0.0])
+            .line_to([10.0, 0.0])
+            .move_to([0.0, 10.0])
+            .line_to([10.0, 10.0]);
+        let (vertices, indices) = PathShape::new(&path).stroke_geometry(
+            &Stroke::new(2.0),
+            Pixel::WHITE,
+            Transform::IDENTITY,
+        );
+
+        assert_eq!(8, vertices.len());
+        assert_eq!(vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7], indices);
+    }
+
+    #[test]
+    fn ellipse_segments_adapt_to_screen_radius() {
+        let ellipse = EllipseShape::new(v2(0.0, 0.0), v2(10.0, 10.0));
+
+        let small = ellipse.segment_count(Transform::IDENTITY);
+        let large = ellipse.segment_count(Transform::scale(v2(10.0, 10.0)));
+
+        assert!(small >= EllipseShape::MIN_SEGMENTS);
+        assert!(large > small);
+        assert!(large <= EllipseShape::MAX_SEGMENTS);
+    }
+
+    #[test]
+    fn ellipse_points_lie_on_ellipse() {
+        let ellipse = EllipseShape::new(v2(5.0, 5.0), v2(20.0, 10.0));
+
+        for point in ellipse.points(Transform::IDENTITY) {
+            let p = point - v2(5.0, 5.0);
+            let d = (p.x / 20.0).powi(2) + (p.y / 10.0).powi(2);
+            assert!((d - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn filled_ellipse_is_a_closed_fan() {
+        let ellipse = EllipseShape::new(v2(0.0, 0.0), v2(1.0, 1.0));
+        let (vertices, indices) = ellipse.fill_geometry(Pixel::WHITE, Transform::IDENTITY);
+        let segments = ellipse.segment_count(Transform::IDENTITY);
+
+        assert_eq!(segments + 1, vertices.len());
+        assert_eq!(segments * 3, indices.len());
+        assert_eq!(&[0, segments as u32, 1], &indices[indices.len() - 3..]);
+    }
+
+    #[test]
+    fn ellipses_without_size_or_sweep_are_empty() {
+        let circle = EllipseShape::new(v2(5.0, 5.0), v2(0.0, 0.0));
+        let arc = EllipseShape::arc(v2(5.0, 5.0), v2(10.0, 10.0), 1.0, 0.0);
+
+        for shape in [circle, arc] {
+            assert!(shape
+                .outline_geometry(2.0, Pixel::WHITE, Transform::IDENTITY)
+                .0
+                .is_empty());
+            assert!(shape
+                .fill_geometry(Pixel::WHITE, Transform::IDENTITY)
+                .0
+                .is_empty());
+            assert!(shape
+                .sector_outline_geometry(2.0, Pixel::WHITE, Transform::IDENTITY)
+                .0
+                .is_empty());
+        }
+
+        let points = [(v2(0.0, 0.0), Pixel::WHITE)];
+        let (_, indices) =
+            PointsShape::new(&points, 0.0).fill_geometry(PointStyle::Round, Transform::IDENTITY);
+        assert!(indices.is_empty());
+    }
+
+    #[test]
+    fn arc_points_span_sweep() {
+        let arc = EllipseShape::arc(v2(0.0, 0.0), v2(10.0, 10.0), 0.0, TAU / 4.0);
+        let points = arc.points(Transform::IDENTITY);
+
+        let first = points.first().expect("first point");
+        let last = points.last().expect("last point");
+        assert!((*first - v2(0.0, 10.0)).len() < 1e-4);
+        assert!((*last - v2(10.0, 0.0)).len() < 1e-4);
+    }
+
+    #[test]
+    fn sector_fill_does_not_close_fan() {
+        let sector = EllipseShape::arc(v2(0.0, 0.0), v2(10.0, 10.0), 0.0, TAU / 4.0);
+        let (vertices, indices) = sector.fill_geometry(Pixel::WHITE, Transform::IDENTITY);
+
+        assert_eq!((vertices.len() - 2) * 3, indices.len());
+    }
+}
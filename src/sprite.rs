@@ -0,0 +1,147 @@
+use mint::Vector2;
+use sgl_math::{v2, Transform, Vec2};
+
+use crate::Pixel;
+
+/// How to place a texture in the scene with [`Scene::draw_sprite`](crate::Scene::draw_sprite).
+///
+/// The origin is the pivot in texture pixels, relative to the top-left of the drawn region. The
+/// sprite is positioned, rotated and scaled around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    pub position: Vec2,
+    pub origin: Vec2,
+    pub rotation: f32,
+    pub scale: Vec2,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub color: Pixel,
+    pub region: Option<(Vec2, Vec2)>,
+}
+
+impl Sprite {
+    pub fn new<V>(position: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        Self {
+            position: position.into().into(),
+            origin: v2(0.0, 0.0),
+            rotation: 0.0,
+            scale: v2(1.0, 1.0),
+            flip_x: false,
+            flip_y: false,
+            color: Pixel::WHITE,
+            region: None,
+        }
+    }
+
+    pub fn with_origin<V>(mut self, origin: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.origin = origin.into().into();
+        self
+    }
+
+    /// Rotation in radians around the origin.
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale<V>(mut self, scale: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.scale = scale.into().into();
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    /// Multiply the texture by `color`.
+    pub fn with_color(mut self, color: Pixel) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Only draw the part of the texture between `from` and `to`, in texture pixels.
+    pub fn with_region<V>(mut self, from: V, to: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.region = Some((from.into().into(), to.into().into()));
+        self
+    }
+
+    pub(crate) fn transform(&self) -> Transform {
+        Transform::translate(self.position)
+            * Transform::rotate(self.rotation)
+            * Transform::scale(self.scale)
+    }
+
+    /// The texture coordinates to sample, swapped on the flipped axes.
+    pub(crate) fn sub_coords(&self, texture_size: Vec2) -> (Vec2, Vec2) {
+        let (mut from, mut to) = self.region.unwrap_or((v2(0.0, 0.0), texture_size));
+
+        if self.flip_x {
+            std::mem::swap(&mut from.x, &mut to.x);
+        }
+
+        if self.flip_y {
+            std::mem::swap(&mut from.y, &mut to.y);
+        }
+
+        (from, to)
+    }
+
+    pub(crate) fn size(&self, texture_size: Vec2) -> Vec2 {
+        let (from, to) = self.region.unwrap_or((v2(0.0, 0.0), texture_size));
+
+        v2((to.x - from.x).abs(), (to.y - from.y).abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_sprite_is_untransformed() {
+        let sprite = Sprite::new([10.0, 20.0]);
+
+        assert_eq!(Transform::translate(v2(10.0, 20.0)), sprite.transform());
+        assert_eq!(Pixel::WHITE, sprite.color);
+    }
+
+    #[test]
+    fn sprite_size_defaults_to_texture_size() {
+        let sprite = Sprite::new([0.0, 0.0]);
+
+        assert_eq!(v2(16.0, 8.0), sprite.size(v2(16.0, 8.0)));
+    }
+
+    #[test]
+    fn sprite_size_uses_region() {
+        let sprite = Sprite::new([0.0, 0.0]).with_region([4.0, 2.0], [12.0, 6.0]);
+
+        assert_eq!(v2(8.0, 4.0), sprite.size(v2(16.0, 8.0)));
+    }
+
+    #[test]
+    fn flipped_sprite_swaps_sub_coords() {
+        let sprite = Sprite::new([0.0, 0.0])
+            .with_region([4.0, 2.0], [12.0, 6.0])
+            .with_flip(true, false);
+
+        assert_eq!(
+            (v2(12.0, 2.0), v2(4.0, 6.0)),
+            sprite.sub_coords(v2(16.0, 8.0))
+        );
+    }
+}
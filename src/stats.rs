@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+/// Counters gathered while rendering a single scene.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RenderStats {
+    pub draw_calls: u32,
+    pub vertices: u32,
+    pub indices: u32,
+    pub pipeline_switches: u32,
+    pub bind_group_switches: u32,
+    pub bytes_uploaded: u64,
+    pub views: u32,
+    /// Time the GPU spent executing the render pass, when the adapter supports timestamp queries.
+    ///
+    /// Timings are read back without stalling, so this reports the most recently completed
+    /// measurement, which is typically a frame or two behind.
+    pub gpu_time: Option<Duration>,
+}
@@ -0,0 +1,86 @@
+/// How the corners between the segments of a polyline are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Extend the outer edges until they meet, falling back to [`LineJoin::Bevel`] when the
+    /// point would be further than the miter limit from the corner.
+    Miter,
+    /// Cut the corner off with a straight edge.
+    Bevel,
+    /// Round the corner off with a circle.
+    Round,
+}
+
+/// How the ends of an open polyline are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    /// End exactly at the first and last point.
+    Butt,
+    /// Extend past the ends by half the thickness.
+    Square,
+    /// Put a half circle on the ends.
+    Round,
+}
+
+/// The thickness, joins, caps and dashes lines are drawn with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    pub thickness: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// Longest a miter can get before it is beveled, as a multiple of the thickness.
+    pub miter_limit: f32,
+    /// Lengths that are drawn and skipped in turn along the line, starting with a drawn one.
+    /// An empty pattern draws a solid line.
+    pub dashes: Vec<f32>,
+    /// How far into the dash pattern the line starts.
+    pub dash_offset: f32,
+}
+
+impl Stroke {
+    pub fn new(thickness: f32) -> Self {
+        Self {
+            thickness,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Break the line up into dashes. Each dash gets the stroke's caps, so a dash of length 0
+    /// with [`LineCap::Round`] draws a dot. An odd number of lengths is repeated to make the
+    /// pattern.
+    pub fn with_dashes(mut self, dashes: &[f32]) -> Self {
+        self.dashes = dashes.to_vec();
+        self
+    }
+
+    /// Shift the dash pattern along the line. Increasing it over time moves the dashes
+    /// backwards along the line, for marching ants.
+    pub fn with_dash_offset(mut self, dash_offset: f32) -> Self {
+        self.dash_offset = dash_offset;
+        self
+    }
+}
+
+impl From<f32> for Stroke {
+    fn from(thickness: f32) -> Self {
+        Self::new(thickness)
+    }
+}
@@ -0,0 +1,638 @@
+use std::{cell::RefCell, collections::HashMap, fmt, path::Path};
+
+use fontdue::FontSettings;
+use sgl_math::{v2, Transform, Vec2};
+
+use crate::{geometry::Vertex, load_file, Backend, Bitmap, Pixel, SglError, Texture};
+
+/// How lines of text are placed across the box they are drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Stretch the spaces so wrapped lines fill the width of a
+    /// [`TextLayout`](crate::TextLayout). Anywhere else, and on the last line of a paragraph,
+    /// it is the same as [`TextAlign::Left`].
+    Justified,
+}
+
+/// How a block of text is placed between the top and bottom of the box it is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// The size, color and layout of text drawn with [`Scene::draw_text_box`](crate::Scene::draw_text_box).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    /// Height of the font, in scene units.
+    pub size: f32,
+    pub color: Pixel,
+    /// Distance between lines, as a multiple of the font's own line height.
+    pub line_height: f32,
+    pub align: TextAlign,
+    pub vertical_align: VerticalAlign,
+}
+
+impl TextStyle {
+    pub fn new(size: f32, color: Pixel) -> Self {
+        Self {
+            size,
+            color,
+            line_height: 1.0,
+            align: TextAlign::Left,
+            vertical_align: VerticalAlign::Top,
+        }
+    }
+
+    pub fn with_line_height(mut self, line_height: f32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign, vertical_align: VerticalAlign) -> Self {
+        self.align = align;
+        self.vertical_align = vertical_align;
+        self
+    }
+
+    /// Where the top of a block of text `height` high goes in the box from `from` to `to`.
+    pub(crate) fn block_top(&self, from: Vec2, to: Vec2, height: f32) -> f32 {
+        let factor = match self.vertical_align {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Middle => 0.5,
+            VerticalAlign::Bottom => 1.0,
+        };
+
+        from.y.min(to.y) + ((from.y - to.y).abs() - height) * factor
+    }
+
+    /// Where the left of a line `width` wide goes in the box from `from` to `to`.
+    pub(crate) fn line_left(&self, from: Vec2, to: Vec2, width: f32) -> f32 {
+        let factor = match self.align {
+            TextAlign::Left | TextAlign::Justified => 0.0,
+            TextAlign::Center => 0.5,
+            TextAlign::Right => 1.0,
+        };
+
+        from.x.min(to.x) + ((from.x - to.x).abs() - width) * factor
+    }
+}
+
+/// A character positioned by a layout, with its pen position on the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PlacedGlyph {
+    pub(crate) c: char,
+    pub(crate) position: Vec2,
+    pub(crate) size: f32,
+    pub(crate) color: Pixel,
+}
+
+/// A TrueType or OpenType font.
+///
+/// Glyphs are rasterized the first time they are drawn at a size, and kept in an atlas texture
+/// owned by the font. Text is rasterized at the size it ends up on screen, so it stays sharp
+/// when the scene is scaled.
+pub struct Font {
+    font: fontdue::Font,
+    atlas: Texture,
+    cache: RefCell<GlyphCache>,
+}
+
+/// Where a glyph is in the atlas and how to place it, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Glyph {
+    /// From the pen position on the baseline to the top-left of the glyph.
+    offset: Vec2,
+    size: Vec2,
+    tex_from: Vec2,
+    tex_to: Vec2,
+}
+
+struct GlyphCache {
+    bitmap: Bitmap,
+    /// Glyphs by character and pixel size.
+    glyphs: HashMap<(char, u32), Glyph>,
+    /// The shelf glyphs are currently being added to, with its top and height.
+    shelf_y: u32,
+    shelf_height: u32,
+    cursor_x: u32,
+    dirty: bool,
+    /// A glyph didn't fit, so the atlas starts over once the current frame is uploaded.
+    full: bool,
+}
+
+impl Font {
+    const ATLAS_SIZE: u32 = 1024;
+    /// Empty space around each glyph so neighbors never bleed into each other.
+    const PADDING: u32 = 1;
+
+    pub fn from_bytes(gpu: &impl Backend, bytes: &[u8]) -> Result<Self, SglError> {
+        let font = fontdue::Font::from_bytes(bytes, FontSettings::default())
+            .map_err(|e| SglError::General(e.to_string()))?;
+
+        let atlas = Texture::new(
+            Self::ATLAS_SIZE,
+            Self::ATLAS_SIZE,
+            gpu,
+            Some("sgl::text::font_atlas"),
+        );
+        let bitmap = Bitmap::from_pixels(
+            Self::ATLAS_SIZE,
+            Self::ATLAS_SIZE,
+            vec![Pixel::rgba(0xff, 0xff, 0xff, 0); (Self::ATLAS_SIZE * Self::ATLAS_SIZE) as usize],
+        )?;
+
+        Ok(Self {
+            font,
+            atlas,
+            cache: RefCell::new(GlyphCache {
+                bitmap,
+                glyphs: HashMap::new(),
+                shelf_y: 0,
+                shelf_height: 0,
+                cursor_x: 0,
+                dirty: true,
+                full: false,
+            }),
+        })
+    }
+
+    pub fn load_file<P>(gpu: &impl Backend, path: P) -> Result<Self, SglError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_bytes(gpu, &load_file(path)?)
+    }
+
+    pub(crate) fn atlas(&self) -> &Texture {
+        &self.atlas
+    }
+
+    /// Distance between lines of text at `size`, in the same units as `size`.
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.font
+            .horizontal_line_metrics(size)
+            .map_or(size, |metrics| metrics.new_line_size)
+    }
+
+    /// Distance from the top of a line of text at `size` to its baseline.
+    pub(crate) fn ascent(&self, size: f32) -> f32 {
+        self.font
+            .horizontal_line_metrics(size)
+            .map_or(size, |metrics| metrics.ascent)
+    }
+
+    /// How far the pen moves for `c` at `size`.
+    pub(crate) fn advance(&self, c: char, size: f32) -> Option<f32> {
+        Some(self.font.metrics(c, size).advance_width)
+    }
+
+    /// Adjustment between the advance of `first` and `second` at `size`.
+    pub(crate) fn kerning(&self, first: char, second: char, size: f32) -> f32 {
+        self.font
+            .horizontal_kern(first, second, size)
+            .unwrap_or(0.0)
+    }
+
+    /// Upload the atlas if glyphs have been added since it was last uploaded. Called once the
+    /// frame's glyphs are laid out, as a full atlas is cleared here so the glyphs already placed
+    /// this frame keep their spot.
+    pub(crate) fn upload_atlas(&self, gpu: &impl Backend) -> Result<(), SglError> {
+        let mut cache = self.cache.borrow_mut();
+
+        if cache.dirty {
+            self.atlas.upload_to_gpu(gpu, &cache.bitmap)?;
+            cache.dirty = false;
+        }
+
+        if cache.full {
+            cache.clear();
+        }
+
+        Ok(())
+    }
+
+    /// A textured triangle list for `text` laid out in the box from `from` to `to`. A box of no
+    /// size works as an anchor point for the alignment.
+    pub(crate) fn text_geometry(
+        &self,
+        text: &str,
+        from: Vec2,
+        to: Vec2,
+        style: &TextStyle,
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        // Lay out with the metrics of the size the text is rasterized at, so the spacing matches
+        // the glyphs.
+        let scale = transform.max_scale();
+        let px = (style.size * scale).round();
+        if scale <= 0.0 || px < 1.0 {
+            return (Vec::new(), Vec::new());
+        }
+
+        let (ascent, line_size) = self
+            .font
+            .horizontal_line_metrics(px)
+            .map_or((px, px), |metrics| (metrics.ascent, metrics.new_line_size));
+        let line_advance = line_size * style.line_height / scale;
+
+        let lines: Vec<&str> = text.split('\n').collect();
+        let top = style.block_top(from, to, line_advance * lines.len() as f32);
+
+        let mut glyphs = Vec::new();
+
+        for (i, line) in lines.iter().enumerate() {
+            let left = style.line_left(from, to, self.line_width(line, px) / scale);
+            let baseline = top + ascent / scale + line_advance * i as f32;
+
+            let mut pen = left;
+            let mut previous = None;
+
+            for c in line.chars() {
+                if let Some(previous) = previous {
+                    pen += self.font.horizontal_kern(previous, c, px).unwrap_or(0.0) / scale;
+                }
+                previous = Some(c);
+
+                glyphs.push(PlacedGlyph {
+                    c,
+                    position: v2(pen, baseline),
+                    size: style.size,
+                    color: style.color,
+                });
+                pen += self.font.metrics(c, px).advance_width / scale;
+            }
+        }
+
+        self.glyphs_geometry(&glyphs, transform)
+    }
+
+    /// A textured triangle list for glyphs placed with their pen position on the baseline.
+    pub(crate) fn glyphs_geometry(
+        &self,
+        glyphs: &[PlacedGlyph],
+        transform: Transform,
+    ) -> (Vec<Vertex>, Vec<u32>) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        let scale = transform.max_scale();
+        if scale <= 0.0 {
+            return (vertices, indices);
+        }
+
+        // Glyphs are rasterized for the screen, so they are put on whole pixels when nothing
+        // rotates them. Flipped text, as in views with y up, grows the other way from there.
+        let snap = transform.x_axis.y == 0.0
+            && transform.y_axis.x == 0.0
+            && transform.x_axis.x > 0.0
+            && transform.y_axis.y != 0.0;
+        let down = transform.y_axis.y.signum();
+
+        let mut cache = self.cache.borrow_mut();
+
+        for placed in glyphs {
+            // Rasterize at whole pixel sizes, so glyphs can be shared between nearby sizes.
+            let px = (placed.size * scale).round();
+            if px < 1.0 {
+                continue;
+            }
+
+            let Some(glyph) = cache.glyph(&self.font, placed.c, px) else {
+                continue;
+            };
+
+            if glyph.size.x <= 0.0 || glyph.size.y <= 0.0 {
+                continue;
+            }
+
+            let position = placed.position + glyph.offset / scale;
+            let corners = if snap {
+                let top_left = transform.transform_point(position);
+                let top_left = v2(top_left.x.round(), top_left.y.round());
+                let bottom_right = top_left + v2(glyph.size.x, glyph.size.y * down);
+                [
+                    top_left,
+                    v2(bottom_right.x, top_left.y),
+                    bottom_right,
+                    v2(top_left.x, bottom_right.y),
+                ]
+            } else {
+                let size = glyph.size / scale;
+                [
+                    position,
+                    position + v2(size.x, 0.0),
+                    position + size,
+                    position + v2(0.0, size.y),
+                ]
+                .map(|corner| transform.transform_point(corner))
+            };
+            let tex_coords = [
+                glyph.tex_from,
+                v2(glyph.tex_to.x, glyph.tex_from.y),
+                glyph.tex_to,
+                v2(glyph.tex_from.x, glyph.tex_to.y),
+            ];
+
+            let fill_color = placed.color.to_array();
+            let first = vertices.len() as u32;
+            vertices.extend(
+                corners.iter().zip(tex_coords).map(|(corner, tex)| {
+                    Vertex::new(corner.to_array(), tex.to_array(), fill_color)
+                }),
+            );
+            indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+
+        (vertices, indices)
+    }
+
+    /// Width of a line of text at `px`, including kerning, in pixels.
+    fn line_width(&self, line: &str, px: f32) -> f32 {
+        let mut width = 0.0;
+        let mut previous = None;
+
+        for c in line.chars() {
+            if let Some(previous) = previous {
+                width += self.font.horizontal_kern(previous, c, px).unwrap_or(0.0);
+            }
+            previous = Some(c);
+
+            width += self.font.metrics(c, px).advance_width;
+        }
+
+        width
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font")
+            .field("name", &self.font.name())
+            .field("atlas", &self.atlas)
+            .field("glyphs", &self.cache.borrow().glyphs.len())
+            .finish()
+    }
+}
+
+impl GlyphCache {
+    /// The glyph for `c` at `px`, rasterized into the atlas if it isn't there yet. `None` when
+    /// the glyph is too large to ever fit, or the atlas is full until it is next uploaded.
+    fn glyph(&mut self, font: &fontdue::Font, c: char, px: f32) -> Option<Glyph> {
+        let key = (c, px as u32);
+        if let Some(glyph) = self.glyphs.get(&key) {
+            return Some(*glyph);
+        }
+
+        let metrics = font.metrics(c, px);
+        let (width, height) = (metrics.width as u32, metrics.height as u32);
+
+        if width + Font::PADDING > Font::ATLAS_SIZE || height + Font::PADDING > Font::ATLAS_SIZE {
+            log::warn!("'{c}' at {px}px is too large for the font atlas and is not drawn");
+            return None;
+        }
+
+        // Every new size adds glyphs, as when zooming, so a full atlas starts over with the
+        // glyphs drawn from the next frame on. Clearing it now would move glyphs already used
+        // this frame, so the ones that don't fit are left out until then.
+        let Some((x, y)) = self.allocate(width, height) else {
+            if !self.full {
+                log::warn!("the font atlas is full and is cleared after this frame");
+            }
+            self.full = true;
+            return None;
+        };
+
+        let (metrics, coverage) = font.rasterize(c, px);
+
+        for row in 0..height {
+            for column in 0..width {
+                let alpha = coverage[(row * width + column) as usize];
+                self.bitmap
+                    .set_pixel(x + column, y + row, Pixel::rgba(0xff, 0xff, 0xff, alpha));
+            }
+        }
+        self.dirty |= width > 0 && height > 0;
+
+        let atlas_size = Font::ATLAS_SIZE as f32;
+        let glyph = Glyph {
+            offset: v2(
+                metrics.xmin as f32,
+                -(metrics.ymin as f32 + metrics.height as f32),
+            ),
+            size: v2(width as f32, height as f32),
+            tex_from: v2(x as f32, y as f32) / atlas_size,
+            tex_to: v2((x + width) as f32, (y + height) as f32) / atlas_size,
+        };
+        self.glyphs.insert(key, glyph);
+
+        Some(glyph)
+    }
+
+    fn clear(&mut self) {
+        self.bitmap
+            .pixels_mut()
+            .fill(Pixel::rgba(0xff, 0xff, 0xff, 0));
+        self.glyphs.clear();
+        self.shelf_y = 0;
+        self.shelf_height = 0;
+        self.cursor_x = 0;
+        self.dirty = true;
+        self.full = false;
+    }
+
+    /// Find room for a `width` by `height` glyph, filling shelves from left to right and top
+    /// to bottom.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if width == 0 || height == 0 {
+            return Some((0, 0));
+        }
+
+        let (padded_width, padded_height) = (width + Font::PADDING, height + Font::PADDING);
+
+        if self.cursor_x + padded_width > Font::ATLAS_SIZE {
+            self.shelf_y += self.shelf_height;
+            self.shelf_height = 0;
+            self.cursor_x = 0;
+        }
+
+        if self.cursor_x + padded_width > Font::ATLAS_SIZE
+            || self.shelf_y + padded_height > Font::ATLAS_SIZE
+        {
+            return None;
+        }
+
+        let position = (self.cursor_x, self.shelf_y);
+        self.cursor_x += padded_width;
+        self.shelf_height = self.shelf_height.max(padded_height);
+
+        Some(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RecordingBackend;
+
+    static FONT: &[u8] = include_bytes!("../examples/assets/FiraSans-Regular.ttf");
+
+    fn font() -> Font {
+        Font::from_bytes(&RecordingBackend::new(), FONT).unwrap()
+    }
+
+    #[test]
+    fn glyphs_are_cached_per_size() {
+        let font = font();
+        let (vertices, indices) = font.text_geometry(
+            "aa",
+            v2(0.0, 0.0),
+            v2(0.0, 0.0),
+            &TextStyle::new(20.0, Pixel::WHITE),
+            Transform::IDENTITY,
+        );
+
+        assert_eq!(8, vertices.len());
+        assert_eq!(12, indices.len());
+        assert_eq!(vertices[0].tex_coords, vertices[4].tex_coords);
+        assert_eq!(1, font.cache.borrow().glyphs.len());
+
+        font.text_geometry(
+            "a",
+            v2(0.0, 0.0),
+            v2(0.0, 0.0),
+            &TextStyle::new(20.0, Pixel::WHITE),
+            Transform::scale(v2(2.0, 2.0)),
+        );
+        assert_eq!(2, font.cache.borrow().glyphs.len());
+    }
+
+    #[test]
+    fn full_atlas_is_cleared_after_upload() {
+        let backend = RecordingBackend::new();
+        let font = Font::from_bytes(&backend, FONT).unwrap();
+
+        let sizes = 100..200;
+        let drawn = {
+            let mut cache = font.cache.borrow_mut();
+            let drawn = sizes
+                .clone()
+                .filter(|px| cache.glyph(&font.font, 'W', *px as f32).is_some())
+                .count();
+
+            assert!(cache.full);
+            assert!(cache.glyph(&font.font, 'W', 5000.0).is_none());
+            drawn
+        };
+        assert!(drawn < sizes.len());
+        assert_eq!(drawn, font.cache.borrow().glyphs.len());
+
+        font.upload_atlas(&backend).unwrap();
+
+        let mut cache = font.cache.borrow_mut();
+        assert!(!cache.full && cache.dirty);
+        assert!(cache.glyphs.is_empty());
+        assert!(cache.glyph(&font.font, 'W', 199.0).is_some());
+    }
+
+    #[test]
+    fn glyphs_placed_before_the_atlas_fills_stay_valid_for_the_frame() {
+        let backend = RecordingBackend::new();
+        let font = Font::from_bytes(&backend, FONT).unwrap();
+        let style = |px: u32| TextStyle::new(px as f32, Pixel::WHITE);
+
+        let (first, _) = font.text_geometry(
+            "W",
+            v2(0.0, 0.0),
+            v2(0.0, 0.0),
+            &style(100),
+            Transform::IDENTITY,
+        );
+        for px in 101..200 {
+            font.text_geometry(
+                "W",
+                v2(0.0, 0.0),
+                v2(0.0, 0.0),
+                &style(px),
+                Transform::IDENTITY,
+            );
+        }
+        assert!(font.cache.borrow().full);
+
+        // The end of the frame uploads what the frame's quads point at, then starts over.
+        font.upload_atlas(&backend).unwrap();
+        let atlas = font.atlas().download(&backend).unwrap();
+
+        let (metrics, coverage) = font.font.rasterize('W', 100.0);
+        let corner = first[0]
+            .tex_coords
+            .map(|c| (c * Font::ATLAS_SIZE as f32) as usize);
+        let placed: Vec<u8> = (0..metrics.height)
+            .flat_map(|row| {
+                let start = (corner[1] + row) * Font::ATLAS_SIZE as usize + corner[0];
+                &atlas.pixels()[start..start + metrics.width]
+            })
+            .map(|pixel| pixel.a)
+            .collect();
+        assert_eq!(coverage, placed);
+        assert!(font.cache.borrow().glyphs.is_empty());
+    }
+
+    #[test]
+    fn spaces_advance_without_quads() {
+        let font = font();
+        let style = TextStyle::new(20.0, Pixel::WHITE);
+        let (vertices, _) = font.text_geometry(
+            "a a",
+            v2(0.0, 0.0),
+            v2(0.0, 0.0),
+            &style,
+            Transform::IDENTITY,
+        );
+
+        assert_eq!(8, vertices.len());
+        assert!(vertices[4].coords[0] > vertices[1].coords[0]);
+    }
+
+    #[test]
+    fn lines_are_aligned_in_box() {
+        let font = font();
+        let style =
+            TextStyle::new(20.0, Pixel::WHITE).with_align(TextAlign::Right, VerticalAlign::Bottom);
+        let (vertices, _) = font.text_geometry(
+            "i\ni",
+            v2(0.0, 0.0),
+            v2(100.0, 100.0),
+            &style,
+            Transform::IDENTITY,
+        );
+
+        let right = vertices.iter().map(|v| v.coords[0]).fold(0.0, f32::max);
+        assert!(right <= 100.0 && right > 90.0);
+
+        // The second line is a line height below the first.
+        let line_height = font.line_height(20.0);
+        assert!((vertices[4].coords[1] - vertices[0].coords[1] - line_height).abs() <= 1.0);
+        assert!(vertices[6].coords[1] <= 100.0);
+    }
+
+    #[test]
+    fn glyphs_are_snapped_to_pixels() {
+        let font = font();
+        let (vertices, _) = font.text_geometry(
+            "a",
+            v2(0.3, 0.6),
+            v2(0.3, 0.6),
+            &TextStyle::new(20.0, Pixel::WHITE),
+            Transform::IDENTITY,
+        );
+
+        assert!(vertices
+            .iter()
+            .all(|v| v.coords[0].fract() == 0.0 && v.coords[1].fract() == 0.0));
+    }
+}
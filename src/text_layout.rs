@@ -0,0 +1,712 @@
+use mint::Vector2;
+use sgl_math::{v2, Transform, Vec2};
+
+use crate::{
+    geometry::Vertex, shape::RectangleShape, text::PlacedGlyph, BitmapFont, Font, Pixel, TextAlign,
+    TextStyle, Texture,
+};
+
+/// Either kind of font, for laying out text with [`TextLayout`].
+#[derive(Debug, Clone, Copy)]
+pub enum TextFont<'a> {
+    Vector(&'a Font),
+    Bitmap(&'a BitmapFont),
+}
+
+impl<'a> From<&'a Font> for TextFont<'a> {
+    fn from(font: &'a Font) -> Self {
+        Self::Vector(font)
+    }
+}
+
+impl<'a> From<&'a BitmapFont> for TextFont<'a> {
+    fn from(font: &'a BitmapFont) -> Self {
+        Self::Bitmap(font)
+    }
+}
+
+impl TextFont<'_> {
+    fn line_height(&self, size: f32) -> f32 {
+        match self {
+            Self::Vector(font) => font.line_height(size),
+            Self::Bitmap(font) => font.line_height(size),
+        }
+    }
+
+    fn ascent(&self, size: f32) -> f32 {
+        match self {
+            Self::Vector(font) => font.ascent(size),
+            Self::Bitmap(font) => font.ascent(size),
+        }
+    }
+
+    fn advance(&self, c: char, size: f32) -> Option<f32> {
+        match self {
+            Self::Vector(font) => font.advance(c, size),
+            Self::Bitmap(font) => font.advance(c, size),
+        }
+    }
+
+    fn kerning(&self, first: char, second: char, size: f32) -> f32 {
+        match self {
+            Self::Vector(font) => font.kerning(first, second, size),
+            Self::Bitmap(font) => font.kerning(first, second, size),
+        }
+    }
+}
+
+/// Text wrapped to a width and broken into lines ahead of drawing, so it can be measured and
+/// revealed a character at a time.
+///
+/// The text can contain markup in square brackets:
+/// - `[color=#f00]...[/color]` changes the color, with `#rgb`, `#rgba`, `#rrggbb` or
+///   `#rrggbbaa`.
+/// - `[size=24]...[/size]` changes the size, in scene units.
+/// - `[icon=name]` puts an icon added with [`TextLayout::with_icon`] in the text, as tall as the
+///   text around it.
+///
+/// `[[` is a literal `[`, as is any bracket that isn't one of the above.
+///
+/// Lines are aligned within the maximum width, or the widest line when there isn't one. The
+/// vertical alignment of the style isn't used, the layout is drawn from its top-left corner.
+#[derive(Debug)]
+pub struct TextLayout<'a> {
+    font: TextFont<'a>,
+    text: String,
+    style: TextStyle,
+    max_width: Option<f32>,
+    icons: Vec<Icon<'a>>,
+    items: Vec<LaidOut>,
+    char_count: usize,
+    line_count: usize,
+    size: Vec2,
+}
+
+#[derive(Debug)]
+struct Icon<'a> {
+    name: String,
+    texture: &'a Texture,
+    region: (Vec2, Vec2),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Span {
+    Char(char),
+    /// Index into the layout's icons.
+    Icon(usize),
+}
+
+/// A character or icon with the style the markup gave it, and its position in the text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Styled {
+    span: Span,
+    size: f32,
+    color: Pixel,
+    index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Item(Styled),
+    Break,
+}
+
+/// A character or icon placed with its pen position on the baseline, relative to the top-left
+/// of the layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LaidOut {
+    item: Styled,
+    position: Vec2,
+}
+
+#[derive(Debug, Default)]
+struct Line {
+    /// Items with their distance from the start of the line.
+    items: Vec<(Styled, f32)>,
+    width: f32,
+    spaces: usize,
+    ends_paragraph: bool,
+}
+
+impl<'a> TextLayout<'a> {
+    pub fn new<F>(font: F, text: &str, style: TextStyle) -> Self
+    where
+        F: Into<TextFont<'a>>,
+    {
+        let mut layout = Self {
+            font: font.into(),
+            text: text.to_string(),
+            style,
+            max_width: None,
+            icons: Vec::new(),
+            items: Vec::new(),
+            char_count: 0,
+            line_count: 0,
+            size: v2(0.0, 0.0),
+        };
+        layout.layout();
+
+        layout
+    }
+
+    /// Wrap lines that would be wider than `max_width` at the last space that fits, or between
+    /// characters for words that don't fit on a line of their own.
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self.layout();
+        self
+    }
+
+    /// Make `texture` available to the markup as `[icon=name]`.
+    pub fn with_icon(self, name: &str, texture: &'a Texture) -> Self {
+        let size = v2(texture.width() as f32, texture.height() as f32);
+        self.with_icon_region(name, texture, v2(0.0, 0.0), size)
+    }
+
+    /// Like [`TextLayout::with_icon`], with only the part of the texture between `from` and
+    /// `to`, in texture pixels.
+    pub fn with_icon_region<V>(mut self, name: &str, texture: &'a Texture, from: V, to: V) -> Self
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.icons.push(Icon {
+            name: name.to_string(),
+            texture,
+            region: (from.into().into(), to.into().into()),
+        });
+        self.layout();
+        self
+    }
+
+    pub fn font(&self) -> TextFont<'a> {
+        self.font
+    }
+
+    /// Width and height of the laid out text, without drawing it.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Number of characters and icons in the text, leaving out markup and line breaks. This is
+    /// the count to reveal all of it with
+    /// [`Scene::draw_text_layout_revealed`](crate::Scene::draw_text_layout_revealed).
+    pub fn char_count(&self) -> usize {
+        self.char_count
+    }
+
+    /// Textured triangle lists for the first `visible` characters and icons, with the layout's
+    /// top-left corner at `position`. With `y_up` icon regions are measured from the bottom-left
+    /// of their textures, the layout itself is still made y-down.
+    pub(crate) fn geometry(
+        &self,
+        position: Vec2,
+        visible: usize,
+        y_up: bool,
+        transform: Transform,
+    ) -> Vec<(&'a Texture, Vec<Vertex>, Vec<u32>)> {
+        let visible_items = self
+            .items
+            .iter()
+            .filter(|laid_out| laid_out.item.index < visible);
+
+        let glyphs: Vec<PlacedGlyph> = visible_items
+            .clone()
+            .filter_map(|laid_out| match laid_out.item.span {
+                Span::Char(c) => Some(PlacedGlyph {
+                    c,
+                    position: position + laid_out.position,
+                    size: laid_out.item.size,
+                    color: laid_out.item.color,
+                }),
+                Span::Icon(_) => None,
+            })
+            .collect();
+
+        let mut draws = match self.font {
+            TextFont::Vector(font) => {
+                let (vertices, indices) = font.glyphs_geometry(&glyphs, transform);
+                vec![(font.atlas(), vertices, indices)]
+            }
+            TextFont::Bitmap(font) => font.glyphs_geometry(&glyphs, transform),
+        };
+
+        for laid_out in visible_items {
+            if let Span::Icon(i) = laid_out.item.span {
+                let icon = &self.icons[i];
+                let bottom_left = position + laid_out.position;
+                let size = v2(self.advance(&laid_out.item), laid_out.item.size);
+
+                let rect = RectangleShape::new(
+                    bottom_left - v2(0.0, size.y),
+                    bottom_left + v2(size.x, 0.0),
+                );
+                let (from, to) = icon.region;
+                let region = if y_up {
+                    let height = icon.texture.height() as f32;
+                    (v2(from.x, height - to.y), v2(to.x, height - from.y))
+                } else {
+                    (from, to)
+                };
+
+                let (vertices, indices) = rect.texture_geometry(
+                    icon.texture,
+                    Some(region),
+                    Pixel::WHITE,
+                    false,
+                    transform,
+                );
+                draws.push((icon.texture, vertices, indices));
+            }
+        }
+
+        draws
+    }
+
+    fn layout(&mut self) {
+        let tokens = self.parse();
+        self.char_count = tokens
+            .iter()
+            .filter(|token| matches!(token, Token::Item(_)))
+            .count();
+
+        let lines = self.break_lines(&tokens);
+        let width = self
+            .max_width
+            .unwrap_or_else(|| lines.iter().map(|line| line.width).fold(0.0, f32::max));
+
+        self.items.clear();
+        self.line_count = lines.len();
+        let mut top = 0.0;
+        let mut widest: f32 = 0.0;
+
+        for line in lines {
+            let sizes = line.items.iter().map(|(item, _)| item.size);
+            let line_height = sizes
+                .clone()
+                .map(|size| self.font.line_height(size))
+                .fold(None, |max: Option<f32>, height| {
+                    Some(max.map_or(height, |max| max.max(height)))
+                })
+                .unwrap_or_else(|| self.font.line_height(self.style.size));
+            let ascent = line
+                .items
+                .iter()
+                .map(|(item, _)| match item.span {
+                    Span::Char(_) => self.font.ascent(item.size),
+                    Span::Icon(_) => item.size,
+                })
+                .fold(None, |max: Option<f32>, ascent| {
+                    Some(max.map_or(ascent, |max| max.max(ascent)))
+                })
+                .unwrap_or_else(|| self.font.ascent(self.style.size));
+
+            let justify = self.style.align == TextAlign::Justified
+                && self.max_width.is_some()
+                && !line.ends_paragraph
+                && line.spaces > 0;
+            let (offset, extra) = match self.style.align {
+                _ if justify => (0.0, (width - line.width) / line.spaces as f32),
+                TextAlign::Left | TextAlign::Justified => (0.0, 0.0),
+                TextAlign::Center => ((width - line.width) / 2.0, 0.0),
+                TextAlign::Right => (width - line.width, 0.0),
+            };
+            widest = widest.max(if justify { width } else { line.width });
+
+            let mut stretch = 0.0;
+            for (item, x) in line.items {
+                if item.span == Span::Char(' ') {
+                    stretch += extra;
+                    continue;
+                }
+
+                self.items.push(LaidOut {
+                    item,
+                    position: v2(offset + x + stretch, top + ascent),
+                });
+            }
+
+            top += line_height * self.style.line_height;
+        }
+
+        self.size = v2(widest, top);
+    }
+
+    /// Split the markup into styled characters, icons and line breaks.
+    fn parse(&self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut colors = vec![self.style.color];
+        let mut sizes = vec![self.style.size];
+        let mut rest = self.text.as_str();
+
+        while let Some(c) = rest.chars().next() {
+            rest = &rest[c.len_utf8()..];
+
+            if c == '\n' {
+                tokens.push(Token::Break);
+                continue;
+            }
+
+            let mut span = Span::Char(c);
+
+            if c == '[' {
+                if let Some(escaped) = rest.strip_prefix('[') {
+                    rest = escaped;
+                } else if let Some((tag, after)) = rest.split_once(']') {
+                    let (name, value) = tag.split_once('=').unwrap_or((tag, ""));
+                    let value = value.trim();
+
+                    let handled = match name.trim() {
+                        "color" => parse_color(value).map(|color| colors.push(color)).is_some(),
+                        "size" => value
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|size| *size > 0.0)
+                            .map(|size| sizes.push(size))
+                            .is_some(),
+                        "/color" if colors.len() > 1 => colors.pop().is_some(),
+                        "/size" if sizes.len() > 1 => sizes.pop().is_some(),
+                        "icon" => match self.icons.iter().position(|icon| icon.name == value) {
+                            Some(i) => {
+                                span = Span::Icon(i);
+                                false
+                            }
+                            None => false,
+                        },
+                        _ => false,
+                    };
+
+                    if handled {
+                        rest = after;
+                        continue;
+                    }
+
+                    if let Span::Icon(_) = span {
+                        rest = after;
+                    }
+                }
+            }
+
+            tokens.push(Token::Item(Styled {
+                span,
+                size: *sizes.last().unwrap_or(&self.style.size),
+                color: *colors.last().unwrap_or(&self.style.color),
+                index: 0,
+            }));
+        }
+
+        let mut index = 0;
+        for token in tokens.iter_mut() {
+            if let Token::Item(item) = token {
+                item.index = index;
+                index += 1;
+            }
+        }
+
+        tokens
+    }
+
+    fn break_lines(&self, tokens: &[Token]) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut line = Line::default();
+        let mut spaces = Vec::new();
+        let mut word = Vec::new();
+
+        for token in tokens.iter().chain([&Token::Break]) {
+            match token {
+                Token::Item(item) if item.span == Span::Char(' ') => {
+                    self.place_word(&mut word, &mut spaces, &mut line, &mut lines);
+                    spaces.push(*item);
+                }
+                Token::Item(item) => word.push(*item),
+                Token::Break => {
+                    self.place_word(&mut word, &mut spaces, &mut line, &mut lines);
+                    spaces.clear();
+
+                    line.ends_paragraph = true;
+                    lines.push(std::mem::take(&mut line));
+                }
+            }
+        }
+
+        lines
+    }
+
+    /// Put the spaces before a word and the word on the line, or start a new line with the
+    /// word when it doesn't fit.
+    fn place_word(
+        &self,
+        word: &mut Vec<Styled>,
+        spaces: &mut Vec<Styled>,
+        line: &mut Line,
+        lines: &mut Vec<Line>,
+    ) {
+        if word.is_empty() {
+            return;
+        }
+
+        let max_width = self.max_width.unwrap_or(f32::INFINITY);
+        let word_width = self.width(word);
+        let spaces_width: f32 = spaces.iter().map(|space| self.advance(space)).sum();
+
+        if !line.items.is_empty() && line.width + spaces_width + word_width > max_width {
+            lines.push(std::mem::take(line));
+        } else {
+            for space in spaces.iter() {
+                self.append(line, *space);
+            }
+        }
+        spaces.clear();
+
+        for item in word.drain(..) {
+            if !line.items.is_empty() && line.width + self.advance(&item) > max_width {
+                lines.push(std::mem::take(line));
+            }
+
+            self.append(line, item);
+        }
+    }
+
+    fn append(&self, line: &mut Line, item: Styled) {
+        let x = line.width
+            + line
+                .items
+                .last()
+                .map_or(0.0, |(previous, _)| self.kerning(previous, &item));
+
+        line.items.push((item, x));
+        line.width = x + self.advance(&item);
+        if item.span == Span::Char(' ') {
+            line.spaces += 1;
+        }
+    }
+
+    fn width(&self, items: &[Styled]) -> f32 {
+        let kerning: f32 = items
+            .windows(2)
+            .map(|pair| self.kerning(&pair[0], &pair[1]))
+            .sum();
+
+        kerning + items.iter().map(|item| self.advance(item)).sum::<f32>()
+    }
+
+    fn advance(&self, item: &Styled) -> f32 {
+        match item.span {
+            Span::Char(c) => self.font.advance(c, item.size).unwrap_or(0.0),
+            Span::Icon(i) => {
+                let (from, to) = self.icons[i].region;
+                let size = to - from;
+
+                if size.y != 0.0 {
+                    item.size * (size.x / size.y).abs()
+                } else {
+                    item.size
+                }
+            }
+        }
+    }
+
+    fn kerning(&self, first: &Styled, second: &Styled) -> f32 {
+        match (first.span, second.span) {
+            (Span::Char(a), Span::Char(b)) if first.size == second.size => {
+                self.font.kerning(a, b, first.size)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// A color written as `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`.
+fn parse_color(text: &str) -> Option<Pixel> {
+    let hex = text.strip_prefix('#')?;
+    let digits: Vec<u8> = hex
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|d| d * 17).collect(),
+        6 | 8 => digits
+            .chunks(2)
+            .map(|pair| pair[0] * 16 + pair[1])
+            .collect(),
+        _ => return None,
+    };
+
+    Some(Pixel::rgba(
+        channels[0],
+        channels[1],
+        channels[2],
+        channels.get(3).copied().unwrap_or(0xff),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bitmap, RecordingBackend};
+
+    /// A grid font where every glyph is 8 wide and 10 high.
+    fn font(backend: &RecordingBackend) -> BitmapFont {
+        let chars: String = (' '..='~').collect();
+        BitmapFont::from_grid(backend, &Bitmap::new(128, 100), 8, 10, &chars).unwrap()
+    }
+
+    fn line_starts(layout: &TextLayout) -> Vec<Vec2> {
+        let mut starts: Vec<Vec2> = Vec::new();
+        for laid_out in &layout.items {
+            if starts
+                .last()
+                .is_none_or(|start| start.y != laid_out.position.y)
+            {
+                starts.push(laid_out.position);
+            }
+        }
+
+        starts
+    }
+
+    #[test]
+    fn markup_sets_color_and_size() {
+        let backend = RecordingBackend::new();
+        let font = font(&backend);
+        let layout = TextLayout::new(
+            &font,
+            "a[color=#f00]b[size=20]c[/size][/color]d[[e[bold]",
+            TextStyle::new(10.0, Pixel::WHITE),
+        );
+
+        let items: Vec<(Span, f32, Pixel)> = layout
+            .items
+            .iter()
+            .map(|laid_out| (laid_out.item.span, laid_out.item.size, laid_out.item.color))
+            .collect();
+        assert_eq!((Span::Char('a'), 10.0, Pixel::WHITE), items[0]);
+        assert_eq!((Span::Char('b'), 10.0, Pixel::RED), items[1]);
+        assert_eq!((Span::Char('c'), 20.0, Pixel::RED), items[2]);
+        assert_eq!((Span::Char('d'), 10.0, Pixel::WHITE), items[3]);
+        assert_eq!(Span::Char('['), items[4].0);
+        assert_eq!(Span::Char('['), items[6].0);
+        assert_eq!(12, layout.char_count());
+
+        // The larger character pushes the baseline of the whole line down.
+        assert!(layout
+            .items
+            .iter()
+            .all(|laid_out| laid_out.position.y == 20.0));
+    }
+
+    #[test]
+    fn colors_are_parsed_in_every_length() {
+        assert_eq!(
+            Some(Pixel::rgba(0xff, 0x00, 0x00, 0xff)),
+            parse_color("#f00")
+        );
+        assert_eq!(
+            Some(Pixel::rgba(0x11, 0x22, 0x33, 0x44)),
+            parse_color("#1234")
+        );
+        assert_eq!(
+            Some(Pixel::rgba(0x12, 0x34, 0x56, 0xff)),
+            parse_color("#123456")
+        );
+        assert_eq!(
+            Some(Pixel::rgba(0x12, 0x34, 0x56, 0x78)),
+            parse_color("#12345678")
+        );
+        assert_eq!(None, parse_color("#12"));
+        assert_eq!(None, parse_color("red"));
+    }
+
+    #[test]
+    fn text_wraps_at_spaces() {
+        let backend = RecordingBackend::new();
+        let font = font(&backend);
+        let layout = TextLayout::new(&font, "aa bb cc\ndd", TextStyle::new(10.0, Pixel::WHITE))
+            .with_max_width(48.0);
+
+        assert_eq!(3, layout.line_count());
+        assert_eq!(
+            vec![v2(0.0, 10.0), v2(0.0, 20.0), v2(0.0, 30.0)],
+            line_starts(&layout)
+        );
+        assert_eq!(v2(40.0, 30.0), layout.size());
+    }
+
+    #[test]
+    fn long_words_are_split() {
+        let backend = RecordingBackend::new();
+        let font = font(&backend);
+        let layout = TextLayout::new(&font, "abcdefgh", TextStyle::new(10.0, Pixel::WHITE))
+            .with_max_width(24.0);
+
+        assert_eq!(3, layout.line_count());
+        assert_eq!(v2(24.0, 30.0), layout.size());
+    }
+
+    #[test]
+    fn lines_are_aligned_and_justified() {
+        let backend = RecordingBackend::new();
+        let font = font(&backend);
+
+        let style = TextStyle::new(10.0, Pixel::WHITE);
+        let right = TextLayout::new(
+            &font,
+            "a b\nc",
+            style.with_align(TextAlign::Right, style.vertical_align),
+        )
+        .with_max_width(40.0);
+        assert_eq!(vec![v2(16.0, 10.0), v2(32.0, 20.0)], line_starts(&right));
+
+        let justified = TextLayout::new(
+            &font,
+            "a b c d",
+            style.with_align(TextAlign::Justified, style.vertical_align),
+        )
+        .with_max_width(48.0);
+        // "a b c" fills 48 by stretching its two spaces to 16, the last line stays as it is.
+        let positions: Vec<f32> = justified
+            .items
+            .iter()
+            .map(|laid_out| laid_out.position.x)
+            .collect();
+        assert_eq!(vec![0.0, 20.0, 40.0, 0.0], positions);
+    }
+
+    #[test]
+    fn icons_take_their_place_in_the_text() {
+        let backend = RecordingBackend::new();
+        let font = font(&backend);
+        let coin = Texture::new(16, 8, &backend, None);
+
+        let layout = TextLayout::new(&font, "a[icon=coin]b", TextStyle::new(10.0, Pixel::WHITE))
+            .with_icon("coin", &coin);
+
+        assert_eq!(3, layout.char_count());
+        assert_eq!(Span::Icon(0), layout.items[1].item.span);
+        assert_eq!(v2(28.0, 10.0), layout.items[2].position);
+    }
+
+    #[test]
+    fn reveal_limits_drawn_characters() {
+        let backend = RecordingBackend::new();
+        let font = font(&backend);
+        let layout = TextLayout::new(&font, "ab cd", TextStyle::new(10.0, Pixel::WHITE));
+
+        let quads = |visible| -> usize {
+            layout
+                .geometry(v2(0.0, 0.0), visible, false, Transform::IDENTITY)
+                .iter()
+                .map(|(_, vertices, _)| vertices.len() / 4)
+                .sum()
+        };
+
+        assert_eq!(0, quads(0));
+        assert_eq!(2, quads(3));
+        assert_eq!(3, quads(4));
+        assert_eq!(4, quads(layout.char_count()));
+    }
+}
@@ -1,134 +1,144 @@
-use wgpu::{
-    AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindingResource,
-    Extent3d, FilterMode, ImageCopyTexture, ImageDataLayout, Origin3d, Sampler, SamplerDescriptor,
-    TextureAspect, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
-    TextureViewDescriptor,
-};
+use std::any::Any;
 
-use crate::{Bitmap, GraphicsDevice, SglError};
+use crate::{Backend, Bitmap, SglError};
 
 #[derive(Debug)]
 pub struct Texture {
-    size: Extent3d,
-    sampler: Sampler,
-    texture: wgpu::Texture,
-    texture_view: TextureView,
-    pub(crate) bind_group: BindGroup,
+    width: u32,
+    height: u32,
+    raw: Box<dyn Any + Send + Sync>,
 }
 
 impl Texture {
-    pub fn new(
-        width: u32,
-        height: u32,
-        gpu: &GraphicsDevice,
-        format: TextureFormat,
-        layout: &BindGroupLayout,
-        label: Option<&str>,
-    ) -> Self {
-        let (size, sampler, texture, texture_view, bind_group) =
-            Self::create_gpu_resources(gpu, width, height, format, layout, label);
-
-        Self {
-            size,
-            sampler,
-            texture,
-            texture_view,
-            bind_group,
-        }
+    pub fn new(width: u32, height: u32, gpu: &impl Backend, label: Option<&str>) -> Self {
+        let raw = gpu.create_texture(width, height, label);
+
+        Self { width, height, raw }
     }
 
     pub fn width(&self) -> u32 {
-        self.size.width
+        self.width
     }
 
     pub fn height(&self) -> u32 {
-        self.size.height
+        self.height
     }
 
-    fn create_gpu_resources(
-        gpu: &GraphicsDevice,
-        width: u32,
-        height: u32,
-        format: TextureFormat,
-        layout: &BindGroupLayout,
-        label: Option<&str>,
-    ) -> (Extent3d, Sampler, wgpu::Texture, TextureView, BindGroup) {
-        let sampler = gpu.device.create_sampler(&SamplerDescriptor {
-            label,
-            address_mode_u: AddressMode::ClampToEdge,
-            address_mode_v: AddressMode::ClampToEdge,
-            address_mode_w: AddressMode::ClampToEdge,
-            mag_filter: FilterMode::Nearest, // TODO: Make sampler configuration configurable.
-            min_filter: FilterMode::Nearest,
-            mipmap_filter: FilterMode::Nearest,
-            ..Default::default()
-        });
-
-        let size = Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
+    /// The backend resources created for this texture, if they were created by a backend using
+    /// type `T`.
+    pub fn raw<T: Any>(&self) -> Option<&T> {
+        self.raw.downcast_ref()
+    }
 
-        let texture = gpu.device.create_texture(&TextureDescriptor {
-            label,
-            size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
-            format,
-            usage: TextureUsages::RENDER_ATTACHMENT
-                | TextureUsages::TEXTURE_BINDING
-                | TextureUsages::COPY_DST
-                | TextureUsages::COPY_SRC,
-        });
-
-        let texture_view = texture.create_view(&TextureViewDescriptor {
-            label,
-            ..Default::default()
-        });
-
-        let bind_group = gpu.device.create_bind_group(&BindGroupDescriptor {
-            label,
-            layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: BindingResource::Sampler(&sampler),
-                },
-                BindGroupEntry {
-                    binding: 1,
-                    resource: BindingResource::TextureView(&texture_view),
-                },
-            ],
-        });
+    pub fn upload_to_gpu(&self, gpu: &impl Backend, bitmap: &Bitmap) -> Result<(), SglError> {
+        if bitmap.width() != self.width || bitmap.height() != self.height {
+            return Err(SglError::General(
+                "bitmap has differing dimensions to texture".to_string(),
+            ));
+        }
+
+        gpu.upload_texture(self, bitmap)
+    }
+
+    /// Upload `bitmap` to the part of the texture with its top left corner at `x`, `y`. The
+    /// bitmap has to fit inside the texture.
+    pub fn upload_region(
+        &self,
+        gpu: &impl Backend,
+        bitmap: &Bitmap,
+        x: u32,
+        y: u32,
+    ) -> Result<(), SglError> {
+        self.upload_bitmap_region(gpu, x, y, bitmap, (0, 0, bitmap.width(), bitmap.height()))
+    }
 
-        (size, sampler, texture, texture_view, bind_group)
+    /// Read the texture back from the GPU, waiting for what was drawn to it so far.
+    pub fn download(&self, gpu: &impl Backend) -> Result<Bitmap, SglError> {
+        gpu.download_texture(self)
     }
 
-    pub fn upload_to_gpu(&self, gpu: &GraphicsDevice, bitmap: &Bitmap) -> Result<(), SglError> {
-        if bitmap.width() != self.size.width || bitmap.height() != self.size.height {
+    /// Upload the `source` area of `bitmap`, given as `(x, y, width, height)`, to the texture
+    /// with its top left corner at `x`, `y`.
+    pub(crate) fn upload_bitmap_region(
+        &self,
+        gpu: &impl Backend,
+        x: u32,
+        y: u32,
+        bitmap: &Bitmap,
+        source: (u32, u32, u32, u32),
+    ) -> Result<(), SglError> {
+        let (source_x, source_y, width, height) = source;
+
+        let fits = |start: u32, length: u32, size: u32| {
+            start.checked_add(length).is_some_and(|end| end <= size)
+        };
+
+        if !fits(source_x, width, bitmap.width()) || !fits(source_y, height, bitmap.height()) {
             return Err(SglError::General(
-                "bitmap has differing dimensions to texture".to_string(),
+                "region lies outside of the bitmap".to_string(),
+            ));
+        }
+
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return Err(SglError::General(
+                "region lies outside of the texture".to_string(),
             ));
         }
 
-        gpu.queue.write_texture(
-            ImageCopyTexture {
-                aspect: TextureAspect::All,
-                texture: &self.texture,
-                mip_level: 0,
-                origin: Origin3d::ZERO,
-            },
-            bitmap,
-            ImageDataLayout {
-                offset: 0,
-                bytes_per_row: std::num::NonZeroU32::new(4 * self.size.width),
-                rows_per_image: std::num::NonZeroU32::new(self.size.height),
-            },
-            self.size,
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        gpu.upload_texture_region(self, x, y, bitmap, source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pixel, RecordingBackend};
+
+    #[test]
+    fn uploaded_regions_are_downloaded_in_place() {
+        let backend = RecordingBackend::new();
+        let texture = Texture::new(3, 2, &backend, None);
+        texture
+            .upload_to_gpu(
+                &backend,
+                &Bitmap::from_pixels(3, 2, [Pixel::BLACK; 6]).unwrap(),
+            )
+            .unwrap();
+
+        let region = Bitmap::from_pixels(2, 1, [Pixel::RED, Pixel::GREEN]).unwrap();
+        texture.upload_region(&backend, &region, 1, 1).unwrap();
+
+        assert_eq!(
+            &[
+                Pixel::BLACK,
+                Pixel::BLACK,
+                Pixel::BLACK,
+                Pixel::BLACK,
+                Pixel::RED,
+                Pixel::GREEN
+            ],
+            texture.download(&backend).unwrap().pixels()
         );
+    }
+
+    #[test]
+    fn regions_must_fit_the_texture() {
+        let backend = RecordingBackend::new();
+        let texture = Texture::new(3, 2, &backend, None);
+        let region = Bitmap::new(2, 2);
 
-        Ok(())
+        assert!(texture.upload_region(&backend, &region, 1, 0).is_ok());
+        assert!(texture.upload_region(&backend, &region, 2, 0).is_err());
+        assert!(texture.upload_region(&backend, &region, 0, 1).is_err());
+        assert!(texture
+            .upload_region(&backend, &region, u32::MAX, 0)
+            .is_err());
+        assert!(texture
+            .upload_region(&backend, &region, 0, u32::MAX)
+            .is_err());
     }
 }
@@ -0,0 +1,438 @@
+use std::cmp::Ordering;
+
+use sgl_math::Vec2;
+
+use crate::{shape::signed_area, FillRule};
+
+/// Triangulate a simple polygon by ear clipping. Holes are first joined to the outer boundary
+/// with a pair of bridge edges, which turns them into one polygon that doubles back on itself.
+///
+/// The returned indices refer to `points` followed by every hole's points, in order. Either
+/// winding works for the boundary and the holes.
+pub(crate) fn triangulate(points: &[Vec2], holes: &[Vec<Vec2>]) -> Vec<u32> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut vertices: Vec<Vec2> = points.to_vec();
+    let mut ring = ring_indices(0, points, true);
+
+    let mut hole_rings: Vec<Vec<u32>> = Vec::with_capacity(holes.len());
+    for hole in holes.iter().filter(|hole| hole.len() >= 3) {
+        hole_rings.push(ring_indices(vertices.len(), hole, false));
+        vertices.extend_from_slice(hole);
+    }
+
+    // Holes furthest to the right are bridged first, so the bridges of later holes can't cross
+    // them.
+    hole_rings.sort_by(|a, b| {
+        let a = rightmost(&vertices, a).1.x;
+        let b = rightmost(&vertices, b).1.x;
+        b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+    });
+
+    for hole in hole_rings {
+        ring = bridge(&vertices, ring, &hole);
+    }
+
+    clip_ears(&vertices, ring)
+}
+
+/// The indices of a polygon's points, wound counter-clockwise as seen with y pointing up for the
+/// boundary and clockwise for holes.
+fn ring_indices(offset: usize, points: &[Vec2], boundary: bool) -> Vec<u32> {
+    let mut ring: Vec<u32> = (offset..offset + points.len()).map(|i| i as u32).collect();
+
+    if (signed_area(points) > 0.0) != boundary {
+        ring.reverse();
+    }
+
+    ring
+}
+
+/// Position in `ring` and coordinates of the point with the largest x.
+fn rightmost(vertices: &[Vec2], ring: &[u32]) -> (usize, Vec2) {
+    ring.iter().map(|i| vertices[*i as usize]).enumerate().fold(
+        (0, vertices[ring[0] as usize]),
+        |best, (i, point)| {
+            if point.x > best.1.x {
+                (i, point)
+            } else {
+                best
+            }
+        },
+    )
+}
+
+/// Join `hole` to `ring` with an edge from the hole's rightmost point to a point of the ring it
+/// can see.
+fn bridge(vertices: &[Vec2], ring: Vec<u32>, hole: &[u32]) -> Vec<u32> {
+    let (hole_start, m) = rightmost(vertices, hole);
+
+    // Cast a ray to the right and find the closest edge it hits.
+    let mut hit: Option<(f32, usize)> = None;
+
+    for i in 0..ring.len() {
+        let j = (i + 1) % ring.len();
+        let a = vertices[ring[i] as usize];
+        let b = vertices[ring[j] as usize];
+
+        if a.y == b.y || (a.y > m.y && b.y > m.y) || (a.y < m.y && b.y < m.y) {
+            continue;
+        }
+
+        let x = a.x + (m.y - a.y) * (b.x - a.x) / (b.y - a.y);
+
+        if x >= m.x && hit.is_none_or(|(best, _)| x < best) {
+            let end = if a.x > b.x { i } else { j };
+            hit = Some((x, end));
+        }
+    }
+
+    let Some((x, mut p)) = hit else {
+        // The hole isn't inside the polygon.
+        return ring;
+    };
+
+    // Another point may sit between the ray and the end of the edge it hit, blocking the view.
+    // The one closest in angle to the ray is visible.
+    let i = Vec2 { x, y: m.y };
+    let end = vertices[ring[p] as usize];
+
+    if end != i {
+        let mut best_angle = f32::MAX;
+
+        for (position, index) in ring.iter().enumerate() {
+            let point = vertices[*index as usize];
+
+            if point == end || point.x < m.x || !in_triangle(point, m, i, end) {
+                continue;
+            }
+
+            let angle = (point.y - m.y).abs() / (point.x - m.x).max(f32::EPSILON);
+
+            if angle < best_angle {
+                best_angle = angle;
+                p = position;
+            }
+        }
+    }
+
+    let mut joined = Vec::with_capacity(ring.len() + hole.len() + 2);
+    joined.extend_from_slice(&ring[..=p]);
+    joined.extend(hole[hole_start..].iter().chain(&hole[..=hole_start]));
+    joined.extend_from_slice(&ring[p..]);
+
+    joined
+}
+
+fn clip_ears(vertices: &[Vec2], mut ring: Vec<u32>) -> Vec<u32> {
+    let mut indices = Vec::with_capacity(ring.len().saturating_sub(2) * 3);
+
+    while ring.len() > 3 {
+        let count = ring.len();
+        let corner = |i: usize| {
+            (
+                ring[(i + count - 1) % count],
+                ring[i],
+                ring[(i + 1) % count],
+            )
+        };
+
+        let ear = (0..count).find(|i| {
+            let (a, b, c) = corner(*i);
+            is_ear(vertices, &ring, a, b, c)
+        });
+
+        // Nothing left is an ear when the polygon isn't simple. Keep cutting so that at least
+        // something gets drawn.
+        let i = ear.unwrap_or(0);
+        let (a, b, c) = corner(i);
+
+        if cross(
+            vertices[a as usize],
+            vertices[b as usize],
+            vertices[c as usize],
+        ) != 0.0
+        {
+            indices.extend([a, b, c]);
+        }
+
+        ring.remove(i);
+    }
+
+    if let [a, b, c] = ring[..] {
+        if cross(
+            vertices[a as usize],
+            vertices[b as usize],
+            vertices[c as usize],
+        ) != 0.0
+        {
+            indices.extend([a, b, c]);
+        }
+    }
+
+    indices
+}
+
+fn is_ear(vertices: &[Vec2], ring: &[u32], a: u32, b: u32, c: u32) -> bool {
+    let (a, b, c) = (
+        vertices[a as usize],
+        vertices[b as usize],
+        vertices[c as usize],
+    );
+
+    let area = cross(a, b, c);
+
+    // Points on a straight line can always be dropped.
+    if area == 0.0 {
+        return true;
+    }
+
+    area > 0.0
+        && !ring.iter().any(|i| {
+            let point = vertices[*i as usize];
+            point != a && point != b && point != c && in_triangle(point, a, b, c)
+        })
+}
+
+/// Positive when `a`, `b`, `c` turn counter-clockwise as seen with y pointing up.
+fn cross(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x)
+}
+
+/// Whether `point` is inside or on the edge of the triangle, whichever way it winds.
+fn in_triangle(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let ab = cross(a, b, point);
+    let bc = cross(b, c, point);
+    let ca = cross(c, a, point);
+
+    (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+}
+
+/// Fill any set of closed outlines, which may cross themselves and each other, by cutting the
+/// area into horizontal bands where no edges cross and filling the spans of each band that are
+/// inside according to `rule`.
+///
+/// Returns the corners of the trapezoids and the indices of their triangles.
+pub(crate) fn fill_trapezoids(contours: &[Vec<Vec2>], rule: FillRule) -> (Vec<Vec2>, Vec<u32>) {
+    // Edges pointing down, with +1 for those that originally pointed down.
+    let mut edges: Vec<(Vec2, Vec2, i32)> = Vec::new();
+
+    for contour in contours {
+        for (i, a) in contour.iter().enumerate() {
+            let b = contour[(i + 1) % contour.len()];
+
+            match a.y.partial_cmp(&b.y) {
+                Some(Ordering::Less) => edges.push((*a, b, 1)),
+                Some(Ordering::Greater) => edges.push((b, *a, -1)),
+                _ => {}
+            }
+        }
+    }
+
+    let mut ys: Vec<f32> = edges
+        .iter()
+        .flat_map(|(top, bottom, _)| [top.y, bottom.y])
+        .collect();
+
+    for (i, a) in edges.iter().enumerate() {
+        for b in edges[i + 1..].iter() {
+            if let Some(y) = crossing(a, b) {
+                ys.push(y);
+            }
+        }
+    }
+
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut active: Vec<(f32, f32, i32)> = Vec::new();
+
+    for band in ys.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+
+        active.clear();
+        active.extend(
+            edges
+                .iter()
+                .filter(|(a, b, _)| a.y <= top && b.y >= bottom)
+                .map(|(a, b, winding)| (x_at(a, b, top), x_at(a, b, bottom), *winding)),
+        );
+        active.sort_by(|a, b| (a.0 + a.1).total_cmp(&(b.0 + b.1)));
+
+        let mut winding = 0;
+        let mut left: Option<(f32, f32)> = None;
+
+        for (top_x, bottom_x, edge_winding) in active.iter() {
+            winding += edge_winding;
+
+            let inside = match rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            };
+
+            match (inside, left) {
+                (true, None) => left = Some((*top_x, *bottom_x)),
+                (false, Some((left_top, left_bottom))) => {
+                    let first = vertices.len() as u32;
+                    vertices.extend([
+                        Vec2 {
+                            x: left_top,
+                            y: top,
+                        },
+                        Vec2 { x: *top_x, y: top },
+                        Vec2 {
+                            x: *bottom_x,
+                            y: bottom,
+                        },
+                        Vec2 {
+                            x: left_bottom,
+                            y: bottom,
+                        },
+                    ]);
+                    indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+                    left = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    (vertices, indices)
+}
+
+fn x_at(top: &Vec2, bottom: &Vec2, y: f32) -> f32 {
+    top.x + (bottom.x - top.x) * (y - top.y) / (bottom.y - top.y)
+}
+
+/// The height at which two edges cross, if they do somewhere other than their ends.
+fn crossing(a: &(Vec2, Vec2, i32), b: &(Vec2, Vec2, i32)) -> Option<f32> {
+    let top = f32::max(a.0.y, b.0.y);
+    let bottom = f32::min(a.1.y, b.1.y);
+
+    if top >= bottom {
+        return None;
+    }
+
+    // How far apart the edges are at the top and bottom of where they overlap.
+    let gap_top = x_at(&a.0, &a.1, top) - x_at(&b.0, &b.1, top);
+    let gap_bottom = x_at(&a.0, &a.1, bottom) - x_at(&b.0, &b.1, bottom);
+
+    if gap_top * gap_bottom >= 0.0 {
+        return None;
+    }
+
+    Some(top + (bottom - top) * gap_top / (gap_top - gap_bottom))
+}
+
+#[cfg(test)]
+mod tests {
+    use sgl_math::v2;
+
+    use super::*;
+
+    fn area(vertices: &[Vec2], indices: &[u32]) -> f32 {
+        indices
+            .chunks(3)
+            .map(|t| {
+                let (a, b, c) = (
+                    vertices[t[0] as usize],
+                    vertices[t[1] as usize],
+                    vertices[t[2] as usize],
+                );
+                cross(a, b, c).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn triangulate_square() {
+        let square = [v2(0.0, 0.0), v2(0.0, 10.0), v2(10.0, 10.0), v2(10.0, 0.0)];
+        let indices = triangulate(&square, &[]);
+
+        assert_eq!(6, indices.len());
+        assert_eq!(100.0, area(&square, &indices));
+    }
+
+    #[test]
+    fn triangulate_concave_polygon() {
+        // An L shape, with the inner corner at (5, 5).
+        let points = [
+            v2(0.0, 0.0),
+            v2(10.0, 0.0),
+            v2(10.0, 5.0),
+            v2(5.0, 5.0),
+            v2(5.0, 10.0),
+            v2(0.0, 10.0),
+        ];
+        let indices = triangulate(&points, &[]);
+
+        assert_eq!(12, indices.len());
+        assert_eq!(75.0, area(&points, &indices));
+    }
+
+    #[test]
+    fn triangulate_star() {
+        let points: Vec<Vec2> = (0..10)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / 10.0;
+                let radius = if i % 2 == 0 { 10.0 } else { 4.0 };
+                v2(angle.cos(), angle.sin()) * radius
+            })
+            .collect();
+        let indices = triangulate(&points, &[]);
+
+        assert_eq!(8 * 3, indices.len());
+        assert!((signed_area(&points).abs() / 2.0 - area(&points, &indices)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn triangulate_polygon_with_holes() {
+        let square = [v2(0.0, 0.0), v2(0.0, 10.0), v2(10.0, 10.0), v2(10.0, 0.0)];
+        let holes = vec![
+            vec![v2(1.0, 1.0), v2(1.0, 3.0), v2(3.0, 3.0), v2(3.0, 1.0)],
+            vec![v2(6.0, 6.0), v2(8.0, 6.0), v2(8.0, 8.0), v2(6.0, 8.0)],
+        ];
+        let indices = triangulate(&square, &holes);
+
+        let vertices: Vec<Vec2> = square
+            .iter()
+            .chain(holes.concat().iter())
+            .copied()
+            .collect();
+        assert!((92.0 - area(&vertices, &indices)).abs() < 1e-4);
+    }
+
+    fn square(from: f32, to: f32) -> Vec<Vec2> {
+        vec![v2(from, from), v2(to, from), v2(to, to), v2(from, to)]
+    }
+
+    #[test]
+    fn fill_rules_treat_nested_contours_differently() {
+        let contours = [square(0.0, 10.0), square(2.0, 8.0)];
+
+        let (vertices, indices) = fill_trapezoids(&contours, FillRule::NonZero);
+        assert!((100.0 - area(&vertices, &indices)).abs() < 1e-3);
+
+        let (vertices, indices) = fill_trapezoids(&contours, FillRule::EvenOdd);
+        assert!((64.0 - area(&vertices, &indices)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fill_self_intersecting_contour() {
+        // A bow tie, crossing itself at (5, 5).
+        let contour = vec![v2(0.0, 0.0), v2(10.0, 10.0), v2(10.0, 0.0), v2(0.0, 10.0)];
+        let (vertices, indices) = fill_trapezoids(&[contour], FillRule::EvenOdd);
+
+        assert!((50.0 - area(&vertices, &indices)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn triangulate_degenerate_polygon() {
+        assert!(triangulate(&[v2(0.0, 0.0), v2(1.0, 1.0)], &[]).is_empty());
+        assert!(triangulate(&[v2(0.0, 0.0), v2(1.0, 1.0), v2(2.0, 2.0)], &[]).is_empty());
+    }
+}
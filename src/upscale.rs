@@ -0,0 +1,93 @@
+use winit::dpi::PhysicalSize;
+
+/// How a low resolution window is stretched over the window, see [`crate::Window::with_low_res`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Upscale {
+    /// Scale by the largest whole number that fits, with black bars around. Every pixel ends up
+    /// the same size.
+    #[default]
+    Integer,
+    /// Fill as much of the window as the aspect ratio allows, with nearest neighbor sampling.
+    /// Pixels can differ in size by one screen pixel.
+    Letterbox,
+    /// Like [`Upscale::Letterbox`], but blending screen pixels on the edges between pixels so
+    /// they all look the same size.
+    SharpBilinear,
+}
+
+impl Upscale {
+    /// Where `source` lands in `target`, as `(x, y, width, height)` in target pixels.
+    pub(crate) fn rect(
+        self,
+        source: PhysicalSize<u32>,
+        target: PhysicalSize<u32>,
+    ) -> (u32, u32, u32, u32) {
+        let source_width = source.width.max(1);
+        let source_height = source.height.max(1);
+
+        let (width, height) = match self {
+            Upscale::Integer => {
+                let scale = (target.width / source_width)
+                    .min(target.height / source_height)
+                    .max(1);
+
+                (source_width * scale, source_height * scale)
+            }
+            Upscale::Letterbox | Upscale::SharpBilinear => {
+                let scale = f64::min(
+                    target.width as f64 / source_width as f64,
+                    target.height as f64 / source_height as f64,
+                );
+
+                (
+                    ((source_width as f64 * scale).round() as u32).max(1),
+                    ((source_height as f64 * scale).round() as u32).max(1),
+                )
+            }
+        };
+
+        (
+            target.width.saturating_sub(width) / 2,
+            target.height.saturating_sub(height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_scale_centers_whole_multiples() {
+        let source = PhysicalSize::new(320, 180);
+
+        assert_eq!(
+            (0, 0, 1280, 720),
+            Upscale::Integer.rect(source, PhysicalSize::new(1280, 720))
+        );
+        assert_eq!(
+            (160, 80, 960, 540),
+            Upscale::Integer.rect(source, PhysicalSize::new(1280, 700))
+        );
+        assert_eq!(
+            (0, 0, 320, 180),
+            Upscale::Integer.rect(source, PhysicalSize::new(200, 100))
+        );
+    }
+
+    #[test]
+    fn letterbox_keeps_the_aspect_ratio() {
+        let source = PhysicalSize::new(320, 180);
+
+        assert_eq!(
+            (0, 90, 1600, 900),
+            Upscale::Letterbox.rect(source, PhysicalSize::new(1600, 1080))
+        );
+        assert_eq!(
+            (96, 0, 1088, 612),
+            Upscale::SharpBilinear.rect(source, PhysicalSize::new(1280, 612))
+        );
+    }
+}
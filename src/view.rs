@@ -1,11 +1,32 @@
 use mint::Vector2;
+use sgl_math::{v2, Aabb, Transform, Vec2};
 
+/// Where the origin of a window's coordinates is, and which way y points.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// The top-left corner, with y pointing down.
+    #[default]
+    TopLeft,
+    /// The bottom-left corner, with y pointing up.
+    BottomLeft,
+    /// The center of the window, with y pointing up.
+    Center,
+}
+
+/// The area of the world drawn to the window, like a 2D camera.
+///
+/// The view is centered on a point of the world and shows an area of `width` by `height` at a
+/// zoom of 1. Zooming in shows less of the world and rotating the view turns the world the other
+/// way on screen.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct View {
-    left: f32,
-    right: f32,
-    bottom: f32,
-    top: f32,
+    center: Vec2,
+    size: Vec2,
+    zoom: f32,
+    rotation: f32,
+    y_up: bool,
+    /// Scale of the coordinates the view is drawn with, for the window's pixel size.
+    scale: Vec2,
     viewport_left: f32,
     viewport_right: f32,
     viewport_bottom: f32,
@@ -15,20 +36,17 @@ pub struct View {
 
 impl View {
     pub fn new(center: impl Into<Vector2<f32>>, width: f32, height: f32) -> Self {
-        let center = center.into();
-
-        let half_width = width / 2.0;
-        let half_height = height / 2.0;
-
         let mut view = Self {
-            left: center.x - half_width,
-            right: center.x + half_width,
-            bottom: center.y + half_height,
-            top: center.y - half_height,
+            center: center.into().into(),
+            size: v2(width, height),
+            zoom: 1.0,
+            rotation: 0.0,
+            y_up: false,
+            scale: v2(1.0, 1.0),
             viewport_left: 0.0,
             viewport_right: 1.0,
-            viewport_bottom: 0.0,
-            viewport_top: 1.0,
+            viewport_bottom: 1.0,
+            viewport_top: 0.0,
             transform: [0.0; 16],
         };
 
@@ -37,28 +55,108 @@ impl View {
         view
     }
 
+    /// A view of a `width` by `height` window with its coordinates starting at `origin`.
+    pub fn from_origin(origin: Origin, width: f32, height: f32) -> Self {
+        match origin {
+            Origin::TopLeft => Self::new([width / 2.0, height / 2.0], width, height),
+            Origin::BottomLeft => {
+                Self::new([width / 2.0, height / 2.0], width, height).with_y_up(true)
+            }
+            Origin::Center => Self::new([0.0, 0.0], width, height).with_y_up(true),
+        }
+    }
+
+    /// Point y up rather than down. Textures are then drawn upright with their coordinates
+    /// measured from the bottom-left corner, text still reads from the top down and rotations
+    /// turn counterclockwise.
+    pub fn with_y_up(mut self, y_up: bool) -> Self {
+        self.y_up = y_up;
+        self.calculate_transform();
+        self
+    }
+
+    pub fn y_up(&self) -> bool {
+        self.y_up
+    }
+
+    /// Draw the view into part of the window rather than all of it, for split screen or a
+    /// minimap. The viewport is given in fractions of the window, from `(0, 0)` at its top-left
+    /// corner to `(1, 1)` at its bottom-right corner.
+    pub fn with_viewport(mut self, viewport: Aabb) -> Self {
+        let (min, max) = (viewport.min(), viewport.max());
+
+        self.viewport_left = min.x.clamp(0.0, 1.0);
+        self.viewport_top = min.y.clamp(0.0, 1.0);
+        self.viewport_right = max.x.clamp(self.viewport_left, 1.0);
+        self.viewport_bottom = max.y.clamp(self.viewport_top, 1.0);
+        self
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.center
+    }
+
+    pub fn set_center<V>(&mut self, center: V)
+    where
+        V: Into<Vector2<f32>>,
+    {
+        self.center = center.into().into();
+        self.calculate_transform();
+    }
+
+    /// Set the area shown at a zoom of 1.
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.size = v2(width, height);
+        self.calculate_transform();
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Zoom in by `zoom` times, or out with a zoom below 1. Zooms of 0 and below are ignored.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        if zoom > 0.0 {
+            self.zoom = zoom;
+            self.calculate_transform();
+        }
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Turn the view by `rotation` radians clockwise, which turns the world counterclockwise on
+    /// screen.
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+        self.calculate_transform();
+    }
+
+    /// Left edge of the area shown, before rotation.
     pub fn left(&self) -> f32 {
-        self.left
+        self.center.x - self.width() / 2.0
     }
 
     pub fn right(&self) -> f32 {
-        self.right
+        self.center.x + self.width() / 2.0
     }
 
     pub fn bottom(&self) -> f32 {
-        self.bottom
+        self.center.y + self.y_sign() * self.height() / 2.0
     }
 
     pub fn top(&self) -> f32 {
-        self.top
+        self.center.y - self.y_sign() * self.height() / 2.0
     }
 
+    /// Width of the area shown, taking zoom into account.
     pub fn width(&self) -> f32 {
-        self.right - self.left
+        self.size.x / self.zoom
     }
 
     pub fn height(&self) -> f32 {
-        self.bottom - self.top
+        self.size.y / self.zoom
     }
 
     pub fn viewport_left(&self) -> f32 {
@@ -81,19 +179,276 @@ impl View {
         self.transform
     }
 
+    /// The inverse of [`View::transform`], from clip space back to the world.
+    pub fn inverse_transform(&self) -> [f32; 16] {
+        to_matrix(Transform::scale(self.scale) * self.clip_to_world())
+    }
+
+    /// Where `point` on a screen of `screen_size` is in the world, through the viewport of the
+    /// view. Screen coordinates start at the top-left corner of the window.
+    pub fn screen_to_world<V>(&self, point: V, screen_size: V) -> Vec2
+    where
+        V: Into<Vector2<f32>>,
+    {
+        let (point, screen_size): (Vec2, Vec2) = (point.into().into(), screen_size.into().into());
+        let (from, to) = self.viewport_area(screen_size);
+
+        let clip = v2(
+            (point.x - from.x) / (to.x - from.x) * 2.0 - 1.0,
+            1.0 - (point.y - from.y) / (to.y - from.y) * 2.0,
+        );
+
+        self.clip_to_world().transform_point(clip)
+    }
+
+    /// Where `point` in the world is on a screen of `screen_size`, the inverse of
+    /// [`View::screen_to_world`].
+    pub fn world_to_screen<V>(&self, point: V, screen_size: V) -> Vec2
+    where
+        V: Into<Vector2<f32>>,
+    {
+        let (point, screen_size): (Vec2, Vec2) = (point.into().into(), screen_size.into().into());
+        let (from, to) = self.viewport_area(screen_size);
+
+        let clip = self.world_to_clip().transform_point(point);
+
+        v2(
+            from.x + (clip.x + 1.0) / 2.0 * (to.x - from.x),
+            from.y + (1.0 - clip.y) / 2.0 * (to.y - from.y),
+        )
+    }
+
+    pub fn viewport(&self) -> Aabb {
+        Aabb::new(
+            v2(self.viewport_left, self.viewport_top),
+            v2(self.viewport_right, self.viewport_bottom),
+        )
+    }
+
+    /// The same view for coordinates scaled by `scale`.
+    pub(crate) fn scaled(&self, scale: Vec2) -> Self {
+        let mut view = Self {
+            scale: v2(self.scale.x * scale.x, self.scale.y * scale.y),
+            ..*self
+        };
+        view.calculate_transform();
+
+        view
+    }
+
+    /// The viewport as `(x, y, width, height)` in pixels of a target of `width` by `height`, at
+    /// least a pixel in size.
+    pub(crate) fn viewport_pixels(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let to_pixels = |fraction: f32, size: u32| (fraction * size as f32).round() as u32;
+
+        let left = to_pixels(self.viewport_left, width).min(width.saturating_sub(1));
+        let top = to_pixels(self.viewport_top, height).min(height.saturating_sub(1));
+        let right = to_pixels(self.viewport_right, width).clamp(left + 1, width.max(1));
+        let bottom = to_pixels(self.viewport_bottom, height).clamp(top + 1, height.max(1));
+
+        (left, top, right - left, bottom - top)
+    }
+
+    /// Corners of the viewport on a screen of `screen_size`.
+    fn viewport_area(&self, screen_size: Vec2) -> (Vec2, Vec2) {
+        (
+            v2(
+                self.viewport_left * screen_size.x,
+                self.viewport_top * screen_size.y,
+            ),
+            v2(
+                self.viewport_right * screen_size.x,
+                self.viewport_bottom * screen_size.y,
+            ),
+        )
+    }
+
+    /// 1 when y points down the screen, -1 when it points up.
+    fn y_sign(&self) -> f32 {
+        if self.y_up {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    fn world_to_clip(&self) -> Transform {
+        Transform::scale(v2(2.0 / self.width(), -self.y_sign() * 2.0 / self.height()))
+            * Transform::rotate(-self.rotation)
+            * Transform::translate(-self.center)
+    }
+
+    fn clip_to_world(&self) -> Transform {
+        Transform::translate(self.center)
+            * Transform::rotate(self.rotation)
+            * Transform::scale(v2(self.width() / 2.0, -self.y_sign() * self.height() / 2.0))
+    }
+
     fn calculate_transform(&mut self) {
-        // http://learnwebgl.brown37.net/08_projections/projections_ortho.html
-        let width = self.right - self.left;
-        let height = self.bottom - self.top;
+        let scale = v2(1.0 / self.scale.x, 1.0 / self.scale.y);
+
+        self.transform = to_matrix(self.world_to_clip() * Transform::scale(scale));
+    }
+}
+
+/// A column-major 4x4 matrix for the shaders, doing `transform` in 2D.
+fn to_matrix(transform: Transform) -> [f32; 16] {
+    let Transform {
+        x_axis,
+        y_axis,
+        translation,
+    } = transform;
+
+    #[rustfmt::skip]
+    let matrix = [
+        x_axis.x,      x_axis.y,      0.0, 0.0,
+        y_axis.x,      y_axis.y,      0.0, 0.0,
+        0.0,           0.0,           0.5, 0.0,
+        translation.x, translation.y, 1.0, 1.0,
+    ];
+
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn viewport_covers_the_window_by_default() {
+        let view = View::new([160.0, 120.0], 320.0, 240.0);
+
+        assert_eq!(Aabb::new(v2(0.0, 0.0), v2(1.0, 1.0)), view.viewport());
+        assert_eq!((0, 0, 640, 480), view.viewport_pixels(640, 480));
+    }
+
+    #[test]
+    fn viewport_is_measured_from_the_top_left() {
+        let bottom_right = View::new([160.0, 120.0], 320.0, 240.0)
+            .with_viewport(Aabb::new(v2(0.5, 0.75), v2(1.0, 1.0)));
+
+        assert_eq!((320, 360, 320, 120), bottom_right.viewport_pixels(640, 480));
+    }
+
+    #[test]
+    fn viewport_is_kept_inside_the_window() {
+        let view = View::new([160.0, 120.0], 320.0, 240.0)
+            .with_viewport(Aabb::new(v2(-1.0, 1.0), v2(2.0, 1.0)));
+
+        assert_eq!((0, 479, 640, 1), view.viewport_pixels(640, 480));
+    }
+
+    #[test]
+    fn scaled_view_keeps_its_viewport() {
+        let viewport = Aabb::new(v2(0.0, 0.0), v2(0.5, 1.0));
+        let view = View::new([160.0, 120.0], 320.0, 240.0).with_viewport(viewport);
+        let scaled = view.scaled(v2(2.0, 2.0));
+
+        assert_eq!(viewport, scaled.viewport());
+        assert_eq!(
+            View::new([320.0, 240.0], 640.0, 480.0).transform(),
+            scaled.transform()
+        );
+    }
+
+    #[test]
+    fn transform_maps_view_to_clip_space() {
+        let view = View::new([160.0, 120.0], 320.0, 240.0);
 
         #[rustfmt::skip]
-        let transform = [
-             2.0 / width,                      0.0,                               0.0, 0.0,
-             0.0,                             -(2.0 / height),                    0.0, 0.0,
-             0.0,                              0.0,                               0.5, 0.0,
-            -(self.right + self.left) / width, (self.top + self.bottom) / height, 1.0, 1.0,
-        ];
-
-        self.transform = transform;
+        assert_eq!(
+            [
+                 2.0 / 320.0, 0.0,          0.0, 0.0,
+                 0.0,        -2.0 / 240.0,  0.0, 0.0,
+                 0.0,         0.0,          0.5, 0.0,
+                -1.0,         1.0,          1.0, 1.0,
+            ],
+            view.transform()
+        );
+    }
+
+    #[test]
+    fn zoom_shrinks_the_area_shown() {
+        let mut view = View::new([0.0, 0.0], 320.0, 240.0);
+        view.set_zoom(2.0);
+        view.set_zoom(0.0);
+
+        assert_eq!(2.0, view.zoom());
+        assert_eq!((-80.0, 80.0), (view.left(), view.right()));
+        assert_eq!((-60.0, 60.0), (view.top(), view.bottom()));
+    }
+
+    #[test]
+    fn screen_and_world_convert_both_ways() {
+        let mut view = View::new([100.0, 50.0], 320.0, 240.0)
+            .with_viewport(Aabb::new(v2(0.5, 0.0), v2(1.0, 1.0)));
+        let screen = v2(640.0, 240.0);
+
+        assert_eq!(
+            v2(480.0, 120.0),
+            view.world_to_screen(v2(100.0, 50.0), screen)
+        );
+        assert_eq!(
+            v2(-60.0, -70.0),
+            view.screen_to_world(v2(320.0, 0.0), screen)
+        );
+
+        view.set_rotation(FRAC_PI_2);
+        view.set_zoom(2.0);
+        let world = view.screen_to_world(v2(400.0, 30.0), screen);
+        let back = view.world_to_screen(world, screen);
+        assert!((back - v2(400.0, 30.0)).len() < 1e-3, "{back:?}");
+    }
+
+    #[test]
+    fn rotation_turns_the_world_the_other_way() {
+        let mut view = View::new([0.0, 0.0], 200.0, 200.0);
+        view.set_rotation(FRAC_PI_2);
+
+        // Turning the view clockwise brings what was to its right to the top of the screen.
+        let screen = view.world_to_screen(v2(50.0, 0.0), v2(200.0, 200.0));
+        assert!((screen - v2(100.0, 50.0)).len() < 1e-3, "{screen:?}");
+    }
+
+    #[test]
+    fn origins_place_window_corners() {
+        let screen = v2(320.0, 240.0);
+        let corners = |origin| {
+            let view = View::from_origin(origin, 320.0, 240.0);
+            (
+                view.screen_to_world(v2(0.0, 0.0), screen),
+                view.screen_to_world(v2(0.0, 240.0), screen),
+            )
+        };
+
+        assert_eq!((v2(0.0, 0.0), v2(0.0, 240.0)), corners(Origin::TopLeft));
+        assert_eq!((v2(0.0, 240.0), v2(0.0, 0.0)), corners(Origin::BottomLeft));
+        assert_eq!(
+            (v2(-160.0, 120.0), v2(-160.0, -120.0)),
+            corners(Origin::Center)
+        );
+
+        let view = View::from_origin(Origin::BottomLeft, 320.0, 240.0);
+        assert_eq!((240.0, 0.0), (view.top(), view.bottom()));
+    }
+
+    #[test]
+    fn inverse_transform_undoes_transform() {
+        let mut view = View::new([30.0, -20.0], 320.0, 240.0).scaled(v2(2.0, 3.0));
+        view.set_rotation(0.5);
+        view.set_zoom(1.5);
+
+        let apply = |m: [f32; 16], p: Vec2| {
+            v2(
+                m[0] * p.x + m[4] * p.y + m[12],
+                m[1] * p.x + m[5] * p.y + m[13],
+            )
+        };
+        let point = v2(12.0, 34.0);
+        let back = apply(view.inverse_transform(), apply(view.transform(), point));
+
+        assert!((back - point).len() < 1e-3, "{back:?}");
     }
 }
@@ -1,4 +1,4 @@
-use sgl_math::v2;
+use sgl_math::{v2, Vec2};
 use winit::{
     dpi::{LogicalSize, PhysicalSize},
     event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
@@ -7,7 +7,7 @@ use winit::{
     window::WindowBuilder,
 };
 
-use crate::{Key, SglError, View};
+use crate::{Key, Origin, SglError, Upscale, View};
 
 pub struct Window {
     pub(crate) pixel_size: PhysicalSize<u32>,
@@ -15,6 +15,9 @@ pub struct Window {
     event_loop: EventLoop<()>,
     pub(crate) native_window: winit::window::Window,
     pub(crate) view: View,
+    pub(crate) low_res: Option<Upscale>,
+    pub(crate) size: Vec2,
+    mouse_position: Vec2,
     key_pressed: [bool; 1],
 }
 
@@ -41,14 +44,8 @@ impl Window {
         let native_window = window_builder
             .build(&event_loop)
             .map_err(|e| SglError::General(e.to_string()))?;
-        let view = View::new(
-            v2(
-                logical_size.width as f32 / 2.0,
-                logical_size.height as f32 / 2.0,
-            ),
-            logical_size.width as f32,
-            logical_size.height as f32,
-        );
+        let size = v2(width as f32, height as f32);
+        let view = View::from_origin(Origin::TopLeft, size.x, size.y);
 
         Ok(Self {
             pixel_size,
@@ -56,18 +53,69 @@ impl Window {
             event_loop,
             native_window,
             view,
+            low_res: None,
+            size,
+            mouse_position: v2(0.0, 0.0),
             key_pressed: [false; 1],
         })
     }
 
+    /// Draw at the resolution given to [`Window::new`] and scale the result up to the window,
+    /// so everything, rotated or not, lands on the same grid of big pixels.
+    ///
+    /// Call this before creating the [`crate::GraphicsDevice`] and [`crate::Renderer`].
+    pub fn with_low_res(mut self, upscale: Upscale) -> Self {
+        self.low_res = Some(upscale);
+        self
+    }
+
     pub fn closed(&self) -> bool {
         self.closed
     }
 
+    /// Size of a pixel of the window on screen, as given to [`Window::new`].
+    pub fn pixel_size(&self) -> Vec2 {
+        v2(self.pixel_size.width as f32, self.pixel_size.height as f32)
+    }
+
     pub fn view(&self) -> View {
         self.view
     }
 
+    /// Start the window's coordinates at `origin`, for the view scenes begin with and
+    /// [`Window::mouse_position`].
+    pub fn set_origin(&mut self, origin: Origin) {
+        self.view = View::from_origin(origin, self.size.x, self.size.y);
+    }
+
+    /// Position of the mouse in the window's coordinates, as last seen over the window.
+    pub fn mouse_position(&self) -> Vec2 {
+        let screen = match self.low_res {
+            Some(upscale) => {
+                let source = PhysicalSize::new(self.size.x as u32, self.size.y as u32);
+                let (x, y, width, height) = upscale.rect(source, self.native_window.inner_size());
+
+                let position = self.mouse_position - v2(x as f32, y as f32);
+
+                v2(
+                    position.x * self.size.x / width as f32,
+                    position.y * self.size.y / height as f32,
+                )
+            }
+            None => {
+                let scale = self.native_window.scale_factor() as f32;
+                let position = self.mouse_position / scale;
+
+                v2(
+                    position.x / self.pixel_size.width as f32,
+                    position.y / self.pixel_size.height as f32,
+                )
+            }
+        };
+
+        self.view.screen_to_world(screen, self.size)
+    }
+
     pub fn key_down(&self, key: Key) -> bool {
         self.key_pressed[key as usize]
     }
@@ -85,24 +133,26 @@ impl Window {
                 } => {
                     self.closed = true;
                 }
-                Event::WindowEvent { window_id, event } if self.native_window.id() == window_id => {
-                    match event {
+                Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::CursorMoved { position, .. },
+                } if self.native_window.id() == window_id => {
+                    self.mouse_position = v2(position.x as f32, position.y as f32);
+                }
+                Event::WindowEvent {
+                    window_id,
+                    event:
                         WindowEvent::KeyboardInput {
                             input:
                                 KeyboardInput {
-                                    virtual_keycode: Some(virtual_code),
+                                    virtual_keycode: Some(VirtualKeyCode::Escape),
                                     state: ElementState::Pressed,
                                     ..
                                 },
                             ..
-                        } => match virtual_code {
-                            VirtualKeyCode::Escape => {
-                                self.key_pressed[Key::Escape as usize] = true;
-                            }
-                            _ => (),
                         },
-                        _ => (),
-                    }
+                } if self.native_window.id() == window_id => {
+                    self.key_pressed[Key::Escape as usize] = true;
                 }
 
                 _ => (),